@@ -3,14 +3,142 @@ import * as wasm from '../pkg/snakegame_wasm.js'
 
 await init(); //Initialise wasm
 
-const TPS = wasm.queryTPS();
-canvas.addEventListener("keydown",(key) => {
-  key.preventDefault();
-  wasm.sendKeypress(key.keyCode);
+// Runs a game, taking keys from its canvas, until the returned function is
+// called, which destroys it
+function run(game) {
+  const canvas = game.canvas;
+  let running = true;
+  const keydown = (key) => {
+    key.preventDefault();
+    game.sendKeypress(key.keyCode);
+  };
+  const keyup = (key) => game.sendKeyRelease(key.keyCode);
+  const blur = () => game.sendKeyRelease(8); // Backspace
+  const resize = () => game.resize();
+  canvas.addEventListener("keydown",keydown);
+  canvas.addEventListener("keyup",keyup);
+  canvas.addEventListener("blur",blur);
+  window.addEventListener("resize",resize);
+  document.addEventListener("fullscreenchange",resize);
+
+  function gameLoop() {
+    if (!running) {return;}
+    game.tick();
+    setTimeout(gameLoop,1000/game.queryTPS());
+  }
+  // Effects animate at the display's frame rate, between game ticks
+  function animate(now) {
+    if (!running) {return;}
+    game.animate(now);
+    requestAnimationFrame(animate);
+  }
+  game.start();
+  gameLoop();
+  requestAnimationFrame(animate);
+
+  return () => {
+    running = false;
+    canvas.removeEventListener("keydown",keydown);
+    canvas.removeEventListener("keyup",keyup);
+    canvas.removeEventListener("blur",blur);
+    window.removeEventListener("resize",resize);
+    document.removeEventListener("fullscreenchange",resize);
+    game.destroy();
+  };
+}
+
+const game = new wasm.Game();
+const canvas = game.canvas;
+run(game);
+
+// Visit with ?bench to compare full and dirty-cell rendering costs
+if (new URLSearchParams(location.search).has("bench")) {
+  console.log(game.benchmarkRender(600));
+}
+
+// Custom game form, every input is named after a GameRules field
+const customGame = document.getElementById("customgame");
+function showRules() {
+  const rules = game.queryRules();
+  for (const input of customGame.querySelectorAll("input")) {
+    if (input.type === "checkbox") {input.checked = rules[input.name];}
+    else {input.value = rules[input.name];}
+  }
+  rules.free();
+}
+customGame.addEventListener("submit",(event) => {
+  event.preventDefault();
+  const rules = game.queryRules();
+  for (const input of customGame.querySelectorAll("input")) {
+    if (input.type === "checkbox") {rules[input.name] = input.checked;}
+    else {rules[input.name] = Number(input.value);}
+  }
+  game.setRules(rules);
+  showRules();
+  canvas.focus();
+});
+customGame.addEventListener("reset",(event) => {
+  event.preventDefault();
+  game.setRules(new wasm.GameRules());
+  showRules();
 });
+showRules();
 
-function gameLoop() {
-  wasm.rustGameLoop();
-  setTimeout(gameLoop,1000/TPS);
+// Theme picker and JSON import
+const themeSelect = document.getElementById("themeselect");
+const themeImport = document.getElementById("themeimport");
+function showThemes(selected = game.queryTheme()) {
+  themeSelect.replaceChildren(...game.queryThemes().map((name) => {
+    return new Option(name,name,false,name === selected);
+  }));
 }
-gameLoop();
+themeSelect.addEventListener("change",() => {
+  game.setTheme(themeSelect.value);
+  canvas.focus();
+});
+themeImport.addEventListener("submit",(event) => {
+  event.preventDefault();
+  const error = document.getElementById("themeerror");
+  try {
+    game.importTheme(themeImport.json.value);
+    showThemes(JSON.parse(themeImport.json.value).name);
+    error.textContent = "";
+  } catch (e) {
+    error.textContent = e;
+  }
+});
+showThemes();
+
+// Audio settings, the volume sliders are named after the Channel they control
+const audioSettings = document.getElementById("audiosettings");
+for (const input of audioSettings.querySelectorAll("input[type=range]")) {
+  input.value = game.queryVolume(wasm.Channel[input.name]);
+  input.addEventListener("input",() => {
+    game.setVolume(wasm.Channel[input.name],Number(input.value));
+  });
+}
+audioSettings.movementTicks.checked = game.queryMovementTicks();
+audioSettings.movementTicks.addEventListener("change",() => {
+  game.setMovementTicks(audioSettings.movementTicks.checked);
+});
+
+// Daily challenge results, refreshed whenever the block is opened
+const dailyBlock = document.getElementById("dailyblock");
+const dailySummary = document.getElementById("dailysummary");
+dailyBlock.addEventListener("toggle",() => {
+  if (!dailyBlock.open) {return;}
+  dailySummary.textContent = game.queryDailySummary()
+    ?? "Today's challenge hasn't been played yet";
+  const history = JSON.parse(game.queryDailyHistory()).reverse();
+  document.getElementById("dailyhistory").replaceChildren(
+    ...history.map((result) => {
+      const item = document.createElement("li");
+      item.textContent = `${result.date}: ${result.score}`;
+      return item;
+    })
+  );
+});
+document.getElementById("dailyshare").addEventListener("click",() => {
+  const summary = game.queryDailySummary();
+  if (summary) {navigator.clipboard.writeText(summary);}
+});
@@ -1,47 +1,186 @@
 /* tslint:disable */
 /* eslint-disable */
-/**
-* @returns {number}
-*/
-export function queryTPS(): number;
-/**
-*/
-export function rustGameLoop(): void;
-/**
-* @param {number} key
-*/
-export function sendKeypress(key: number): void;
-/**
-*/
-export function main(): void;
+
+export enum Channel {
+    Master = 0,
+    Sfx = 1,
+    Music = 2,
+}
+
+export enum EdgeMode {
+    Wrap = 0,
+    Solid = 1,
+    Mirror = 2,
+}
+
+export class Game {
+    free(): void;
+    [Symbol.dispose](): void;
+    animate(now: number): void;
+    benchmarkRender(frames: number): string;
+    destroy(): void;
+    importTheme(json: string): void;
+    constructor(container?: HTMLElement | null, options?: MountOptions | null);
+    queryDailyHistory(): string;
+    queryDailySummary(): string | undefined;
+    queryMovementTicks(): boolean;
+    queryRules(): GameRules;
+    queryStats(): string;
+    queryTPS(): number;
+    queryTheme(): string;
+    queryThemes(): string[];
+    queryVolume(channel: Channel): number;
+    resize(): void;
+    sendKeyRelease(key: number): void;
+    sendKeypress(key: number): void;
+    setMovementTicks(enabled: boolean): void;
+    setRules(rules: GameRules): void;
+    setTheme(name: string): void;
+    setVolume(channel: Channel, volume: number): void;
+    start(): void;
+    tick(): void;
+    toggleFullscreen(): void;
+    readonly canvas: HTMLCanvasElement;
+}
+
+export class GameRules {
+    free(): void;
+    [Symbol.dispose](): void;
+    constructor();
+    appleTarget: number;
+    buildWalls: boolean;
+    edgeMode: EdgeMode;
+    expiringApples: boolean;
+    feast: boolean;
+    fullBoardBonus: number;
+    graceTicks: number;
+    growthPerApple: number;
+    level: number;
+    scorePerApple: number;
+    startingLength: number;
+    tickRate: number;
+}
+
+export class MountOptions {
+    free(): void;
+    [Symbol.dispose](): void;
+    constructor();
+    assets: string;
+    canvas: string;
+    combo: string;
+    highScore: string;
+    hud: string;
+    namespace: string;
+    score: string;
+    toast: string;
+}
 
 export type InitInput = RequestInfo | URL | Response | BufferSource | WebAssembly.Module;
 
 export interface InitOutput {
-  readonly memory: WebAssembly.Memory;
-  readonly queryTPS: () => number;
-  readonly rustGameLoop: () => void;
-  readonly sendKeypress: (a: number) => void;
-  readonly main: () => void;
-  readonly __wbindgen_exn_store: (a: number) => void;
-  readonly __wbindgen_start: () => void;
+    readonly memory: WebAssembly.Memory;
+    readonly __wbg_game_free: (a: number, b: number) => void;
+    readonly __wbg_gamerules_free: (a: number, b: number) => void;
+    readonly __wbg_get_gamerules_appleTarget: (a: number) => number;
+    readonly __wbg_get_gamerules_buildWalls: (a: number) => number;
+    readonly __wbg_get_gamerules_edgeMode: (a: number) => number;
+    readonly __wbg_get_gamerules_expiringApples: (a: number) => number;
+    readonly __wbg_get_gamerules_feast: (a: number) => number;
+    readonly __wbg_get_gamerules_fullBoardBonus: (a: number) => number;
+    readonly __wbg_get_gamerules_graceTicks: (a: number) => number;
+    readonly __wbg_get_gamerules_growthPerApple: (a: number) => number;
+    readonly __wbg_get_gamerules_level: (a: number) => number;
+    readonly __wbg_get_gamerules_scorePerApple: (a: number) => number;
+    readonly __wbg_get_gamerules_startingLength: (a: number) => number;
+    readonly __wbg_get_gamerules_tickRate: (a: number) => number;
+    readonly __wbg_get_mountoptions_assets: (a: number) => [number, number];
+    readonly __wbg_get_mountoptions_canvas: (a: number) => [number, number];
+    readonly __wbg_get_mountoptions_combo: (a: number) => [number, number];
+    readonly __wbg_get_mountoptions_highScore: (a: number) => [number, number];
+    readonly __wbg_get_mountoptions_hud: (a: number) => [number, number];
+    readonly __wbg_get_mountoptions_namespace: (a: number) => [number, number];
+    readonly __wbg_get_mountoptions_score: (a: number) => [number, number];
+    readonly __wbg_get_mountoptions_toast: (a: number) => [number, number];
+    readonly __wbg_mountoptions_free: (a: number, b: number) => void;
+    readonly __wbg_set_gamerules_appleTarget: (a: number, b: number) => void;
+    readonly __wbg_set_gamerules_buildWalls: (a: number, b: number) => void;
+    readonly __wbg_set_gamerules_edgeMode: (a: number, b: number) => void;
+    readonly __wbg_set_gamerules_expiringApples: (a: number, b: number) => void;
+    readonly __wbg_set_gamerules_feast: (a: number, b: number) => void;
+    readonly __wbg_set_gamerules_fullBoardBonus: (a: number, b: number) => void;
+    readonly __wbg_set_gamerules_graceTicks: (a: number, b: number) => void;
+    readonly __wbg_set_gamerules_growthPerApple: (a: number, b: number) => void;
+    readonly __wbg_set_gamerules_level: (a: number, b: number) => void;
+    readonly __wbg_set_gamerules_scorePerApple: (a: number, b: number) => void;
+    readonly __wbg_set_gamerules_startingLength: (a: number, b: number) => void;
+    readonly __wbg_set_gamerules_tickRate: (a: number, b: number) => void;
+    readonly __wbg_set_mountoptions_assets: (a: number, b: number, c: number) => void;
+    readonly __wbg_set_mountoptions_canvas: (a: number, b: number, c: number) => void;
+    readonly __wbg_set_mountoptions_combo: (a: number, b: number, c: number) => void;
+    readonly __wbg_set_mountoptions_highScore: (a: number, b: number, c: number) => void;
+    readonly __wbg_set_mountoptions_hud: (a: number, b: number, c: number) => void;
+    readonly __wbg_set_mountoptions_namespace: (a: number, b: number, c: number) => void;
+    readonly __wbg_set_mountoptions_score: (a: number, b: number, c: number) => void;
+    readonly __wbg_set_mountoptions_toast: (a: number, b: number, c: number) => void;
+    readonly game_animate: (a: number, b: number) => void;
+    readonly game_benchmarkRender: (a: number, b: number) => [number, number];
+    readonly game_canvas: (a: number) => any;
+    readonly game_destroy: (a: number) => void;
+    readonly game_importTheme: (a: number, b: number, c: number) => [number, number];
+    readonly game_mount: (a: number, b: number) => [number, number, number];
+    readonly game_queryDailyHistory: (a: number) => [number, number];
+    readonly game_queryDailySummary: (a: number) => [number, number];
+    readonly game_queryMovementTicks: (a: number) => number;
+    readonly game_queryRules: (a: number) => number;
+    readonly game_queryStats: (a: number) => [number, number];
+    readonly game_queryTPS: (a: number) => number;
+    readonly game_queryTheme: (a: number) => [number, number];
+    readonly game_queryThemes: (a: number) => [number, number];
+    readonly game_queryVolume: (a: number, b: number) => number;
+    readonly game_resize: (a: number) => void;
+    readonly game_sendKeyRelease: (a: number, b: number) => void;
+    readonly game_sendKeypress: (a: number, b: number) => void;
+    readonly game_setMovementTicks: (a: number, b: number) => void;
+    readonly game_setRules: (a: number, b: number) => void;
+    readonly game_setTheme: (a: number, b: number, c: number) => [number, number];
+    readonly game_setVolume: (a: number, b: number, c: number) => void;
+    readonly game_start: (a: number) => void;
+    readonly game_tick: (a: number) => void;
+    readonly game_toggleFullscreen: (a: number) => void;
+    readonly gamerules_standard: () => number;
+    readonly mountoptions_standard: () => number;
+    readonly wasm_bindgen__convert__closures_____invoke__h8f78cfe9d488cff0: (a: number, b: number, c: any) => [number, number];
+    readonly wasm_bindgen__convert__closures_____invoke__h3fcb4711f22968ef: (a: number, b: number) => void;
+    readonly __wbindgen_exn_store: (a: number) => void;
+    readonly __externref_table_alloc: () => number;
+    readonly __wbindgen_externrefs: WebAssembly.Table;
+    readonly __wbindgen_malloc: (a: number, b: number) => number;
+    readonly __wbindgen_realloc: (a: number, b: number, c: number, d: number) => number;
+    readonly __wbindgen_destroy_closure: (a: number, b: number) => void;
+    readonly __wbindgen_free: (a: number, b: number, c: number) => void;
+    readonly __externref_table_dealloc: (a: number) => void;
+    readonly __externref_drop_slice: (a: number, b: number) => void;
+    readonly __wbindgen_start: () => void;
 }
 
+export type SyncInitInput = BufferSource | WebAssembly.Module;
+
 /**
-* Synchronously compiles the given `bytes` and instantiates the WebAssembly module.
-*
-* @param {BufferSource} bytes
-*
-* @returns {InitOutput}
-*/
-export function initSync(bytes: BufferSource): InitOutput;
+ * Instantiates the given `module`, which can either be bytes or
+ * a precompiled `WebAssembly.Module`.
+ *
+ * @param {{ module: SyncInitInput }} module - Passing `SyncInitInput` directly is deprecated.
+ *
+ * @returns {InitOutput}
+ */
+export function initSync(module: { module: SyncInitInput } | SyncInitInput): InitOutput;
 
 /**
-* If `module_or_path` is {RequestInfo} or {URL}, makes a request and
-* for everything else, calls `WebAssembly.instantiate` directly.
-*
-* @param {InitInput | Promise<InitInput>} module_or_path
-*
-* @returns {Promise<InitOutput>}
-*/
-export default function init (module_or_path?: InitInput | Promise<InitInput>): Promise<InitOutput>;
+ * If `module_or_path` is {RequestInfo} or {URL}, makes a request and
+ * for everything else, calls `WebAssembly.instantiate` directly.
+ *
+ * @param {{ module_or_path: InitInput | Promise<InitInput> }} module_or_path - Passing `InitInput` directly is deprecated.
+ *
+ * @returns {Promise<InitOutput>}
+ */
+export default function __wbg_init (module_or_path?: { module_or_path: InitInput | Promise<InitInput> } | InitInput | Promise<InitInput>): Promise<InitOutput>;
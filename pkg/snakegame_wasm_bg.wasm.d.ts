@@ -1,9 +1,85 @@
 /* tslint:disable */
 /* eslint-disable */
 export const memory: WebAssembly.Memory;
-export function queryTPS(): number;
-export function rustGameLoop(): void;
-export function sendKeypress(a: number): void;
-export function main(): void;
-export function __wbindgen_exn_store(a: number): void;
-export function __wbindgen_start(): void;
+export const __wbg_game_free: (a: number, b: number) => void;
+export const __wbg_gamerules_free: (a: number, b: number) => void;
+export const __wbg_get_gamerules_appleTarget: (a: number) => number;
+export const __wbg_get_gamerules_buildWalls: (a: number) => number;
+export const __wbg_get_gamerules_edgeMode: (a: number) => number;
+export const __wbg_get_gamerules_expiringApples: (a: number) => number;
+export const __wbg_get_gamerules_feast: (a: number) => number;
+export const __wbg_get_gamerules_fullBoardBonus: (a: number) => number;
+export const __wbg_get_gamerules_graceTicks: (a: number) => number;
+export const __wbg_get_gamerules_growthPerApple: (a: number) => number;
+export const __wbg_get_gamerules_level: (a: number) => number;
+export const __wbg_get_gamerules_scorePerApple: (a: number) => number;
+export const __wbg_get_gamerules_startingLength: (a: number) => number;
+export const __wbg_get_gamerules_tickRate: (a: number) => number;
+export const __wbg_get_mountoptions_assets: (a: number) => [number, number];
+export const __wbg_get_mountoptions_canvas: (a: number) => [number, number];
+export const __wbg_get_mountoptions_combo: (a: number) => [number, number];
+export const __wbg_get_mountoptions_highScore: (a: number) => [number, number];
+export const __wbg_get_mountoptions_hud: (a: number) => [number, number];
+export const __wbg_get_mountoptions_namespace: (a: number) => [number, number];
+export const __wbg_get_mountoptions_score: (a: number) => [number, number];
+export const __wbg_get_mountoptions_toast: (a: number) => [number, number];
+export const __wbg_mountoptions_free: (a: number, b: number) => void;
+export const __wbg_set_gamerules_appleTarget: (a: number, b: number) => void;
+export const __wbg_set_gamerules_buildWalls: (a: number, b: number) => void;
+export const __wbg_set_gamerules_edgeMode: (a: number, b: number) => void;
+export const __wbg_set_gamerules_expiringApples: (a: number, b: number) => void;
+export const __wbg_set_gamerules_feast: (a: number, b: number) => void;
+export const __wbg_set_gamerules_fullBoardBonus: (a: number, b: number) => void;
+export const __wbg_set_gamerules_graceTicks: (a: number, b: number) => void;
+export const __wbg_set_gamerules_growthPerApple: (a: number, b: number) => void;
+export const __wbg_set_gamerules_level: (a: number, b: number) => void;
+export const __wbg_set_gamerules_scorePerApple: (a: number, b: number) => void;
+export const __wbg_set_gamerules_startingLength: (a: number, b: number) => void;
+export const __wbg_set_gamerules_tickRate: (a: number, b: number) => void;
+export const __wbg_set_mountoptions_assets: (a: number, b: number, c: number) => void;
+export const __wbg_set_mountoptions_canvas: (a: number, b: number, c: number) => void;
+export const __wbg_set_mountoptions_combo: (a: number, b: number, c: number) => void;
+export const __wbg_set_mountoptions_highScore: (a: number, b: number, c: number) => void;
+export const __wbg_set_mountoptions_hud: (a: number, b: number, c: number) => void;
+export const __wbg_set_mountoptions_namespace: (a: number, b: number, c: number) => void;
+export const __wbg_set_mountoptions_score: (a: number, b: number, c: number) => void;
+export const __wbg_set_mountoptions_toast: (a: number, b: number, c: number) => void;
+export const game_animate: (a: number, b: number) => void;
+export const game_benchmarkRender: (a: number, b: number) => [number, number];
+export const game_canvas: (a: number) => any;
+export const game_destroy: (a: number) => void;
+export const game_importTheme: (a: number, b: number, c: number) => [number, number];
+export const game_mount: (a: number, b: number) => [number, number, number];
+export const game_queryDailyHistory: (a: number) => [number, number];
+export const game_queryDailySummary: (a: number) => [number, number];
+export const game_queryMovementTicks: (a: number) => number;
+export const game_queryRules: (a: number) => number;
+export const game_queryStats: (a: number) => [number, number];
+export const game_queryTPS: (a: number) => number;
+export const game_queryTheme: (a: number) => [number, number];
+export const game_queryThemes: (a: number) => [number, number];
+export const game_queryVolume: (a: number, b: number) => number;
+export const game_resize: (a: number) => void;
+export const game_sendKeyRelease: (a: number, b: number) => void;
+export const game_sendKeypress: (a: number, b: number) => void;
+export const game_setMovementTicks: (a: number, b: number) => void;
+export const game_setRules: (a: number, b: number) => void;
+export const game_setTheme: (a: number, b: number, c: number) => [number, number];
+export const game_setVolume: (a: number, b: number, c: number) => void;
+export const game_start: (a: number) => void;
+export const game_tick: (a: number) => void;
+export const game_toggleFullscreen: (a: number) => void;
+export const gamerules_standard: () => number;
+export const mountoptions_standard: () => number;
+export const wasm_bindgen__convert__closures_____invoke__h8f78cfe9d488cff0: (a: number, b: number, c: any) => [number, number];
+export const wasm_bindgen__convert__closures_____invoke__h3fcb4711f22968ef: (a: number, b: number) => void;
+export const __wbindgen_exn_store: (a: number) => void;
+export const __externref_table_alloc: () => number;
+export const __wbindgen_externrefs: WebAssembly.Table;
+export const __wbindgen_malloc: (a: number, b: number) => number;
+export const __wbindgen_realloc: (a: number, b: number, c: number, d: number) => number;
+export const __wbindgen_destroy_closure: (a: number, b: number) => void;
+export const __wbindgen_free: (a: number, b: number, c: number) => void;
+export const __externref_table_dealloc: (a: number) => void;
+export const __externref_drop_slice: (a: number, b: number) => void;
+export const __wbindgen_start: () => void;
@@ -0,0 +1,145 @@
+use crate::game_logic::{Board,GameEvent,MAX_COMBO};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Achievement {
+  FirstBite,
+  ComboBreaker,
+  CloseCalls,
+  WallMuncher,
+  OpenRoad,
+  NoRoomLeft
+}
+
+// How an achievement is counted towards: the best a single game has managed,
+// or a running total over every game.
+enum Progress {Best(u32), Add(u32)}
+
+impl Achievement {
+  pub const ALL:[Achievement;6] = [
+    Achievement::FirstBite,
+    Achievement::ComboBreaker,
+    Achievement::CloseCalls,
+    Achievement::WallMuncher,
+    Achievement::OpenRoad,
+    Achievement::NoRoomLeft,
+  ];
+
+  pub fn name(self) -> &'static str {
+    match self {
+      Achievement::FirstBite    => "First Bite",
+      Achievement::ComboBreaker => "Combo Breaker",
+      Achievement::CloseCalls   => "Close Calls",
+      Achievement::WallMuncher  => "Wall Muncher",
+      Achievement::OpenRoad     => "Open Road",
+      Achievement::NoRoomLeft   => "No Room Left"
+    }
+  }
+
+  pub fn description(self) -> &'static str {
+    match self {
+      Achievement::FirstBite    => "Eat an apple",
+      Achievement::ComboBreaker => "Reach the highest combo",
+      Achievement::CloseCalls   => "Survive on grace 10 times in one game",
+      Achievement::WallMuncher  => "Eat 100 apples with walls up",
+      Achievement::OpenRoad     => "Reach length 100 without walls",
+      Achievement::NoRoomLeft   => "Fill the board"
+    }
+  }
+
+  pub fn goal(self) -> u32 {
+    match self {
+      Achievement::FirstBite    => 1,
+      Achievement::ComboBreaker => MAX_COMBO,
+      Achievement::CloseCalls   => 10,
+      Achievement::WallMuncher  => 100,
+      Achievement::OpenRoad     => 100,
+      Achievement::NoRoomLeft   => 1
+    }
+  }
+
+  fn measure(self, event:&GameEvent, board:&Board) -> Progress {
+    let walls = board.query_rules().build_walls;
+    match self {
+      Achievement::FirstBite    => Progress::Best(board.query_apples_eaten()),
+      Achievement::ComboBreaker => Progress::Best(board.query_combo()),
+      Achievement::CloseCalls   => Progress::Best(board.query_grace_saves()),
+      Achievement::WallMuncher  => Progress::Add(
+        (walls && matches!(event,GameEvent::AppleCollected)) as u32
+      ),
+      Achievement::OpenRoad     => Progress::Best(
+        if walls {0} else {board.query_length() as u32}
+      ),
+      Achievement::NoRoomLeft   => Progress::Best(board.query_filled() as u32)
+    }
+  }
+}
+
+// Progress towards every achievement, kept in storage as JSON. Anything at
+// or past its goal is unlocked.
+#[derive(Debug)]
+pub struct Achievements {
+  progress:BTreeMap<String,u32>, // by achievement, see Achievement::ALL
+}
+
+impl Achievements {
+  pub const fn init() -> Self {
+    Achievements {progress: BTreeMap::new()}
+  }
+
+  pub fn from_json(json:&str) -> Self {
+    Achievements {progress: serde_json::from_str(json).unwrap_or_default()}
+  }
+
+  pub fn to_json(&self) -> String {
+    serde_json::to_string(&self.progress).unwrap_or_default()
+  }
+
+  pub fn progress(&self, achievement:Achievement) -> u32 {
+    self.progress.get(&format!("{achievement:?}")).copied().unwrap_or(0)
+  }
+
+  pub fn is_unlocked(&self, achievement:Achievement) -> bool {
+    self.progress(achievement) >= achievement.goal()
+  }
+
+  // Counts a tick of the game, returning anything it unlocked.
+  pub fn update(&mut self, event:&GameEvent, board:&Board)
+    -> Vec<Achievement> {
+    let mut unlocked = Vec::new();
+    for achievement in Achievement::ALL {
+      if self.is_unlocked(achievement) {continue}
+      let progress = match achievement.measure(event,board) {
+        Progress::Best(n) => self.progress(achievement).max(n),
+        Progress::Add(n)  => self.progress(achievement) + n
+      };
+      self.progress.insert(format!("{achievement:?}"),progress);
+      if self.is_unlocked(achievement) {unlocked.push(achievement)}
+    }
+    unlocked
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::game_logic::Direction;
+  use crate::rules::GameRules;
+
+  #[test]
+  fn keeps_best_progress_and_round_trips() {
+    let rules = GameRules {build_walls: false, ..GameRules::init()};
+    let mut board = Board::new(rules);
+    board.place_snake(5,5,3,Direction::Rt);
+    let mut achievements = Achievements::init();
+    for _ in 0..2 {
+      let event = board.do_game_tick();
+      assert_eq!(achievements.update(&event,&board),[]);
+    }
+    assert_eq!(achievements.progress(Achievement::OpenRoad),3);
+    assert_eq!(achievements.progress(Achievement::WallMuncher),0);
+    let loaded = Achievements::from_json(&achievements.to_json());
+    assert_eq!(loaded.progress(Achievement::OpenRoad),3);
+    assert!(!loaded.is_unlocked(Achievement::OpenRoad));
+  }
+}
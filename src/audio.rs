@@ -0,0 +1,157 @@
+use crate::game_logic::GRID_W;
+use crate::music::{Music,Track};
+use crate::storage::Storage;
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{AudioBuffer, AudioContext, GainNode};
+
+const PAN_WIDTH:f32 = 0.8; // how far to the sides the edges of the board pan
+
+#[derive(Debug, Clone, Copy)]
+pub enum Sound {Apple, Grace, GameOver, Movement}
+
+impl Sound {
+  const ALL:[Sound;4] =
+    [Sound::Apple,Sound::Grace,Sound::GameOver,Sound::Movement];
+  // Relative to the assets directory.
+  fn path(self) -> &'static str {
+    match self {
+      Sound::Apple    => "sounds/apple.wav",
+      Sound::Grace    => "sounds/grace.wav",
+      Sound::GameOver => "sounds/gameover.wav",
+      Sound::Movement => "sounds/movement.wav"
+    }
+  }
+}
+
+// Volume controls, each a gain stage: sound effects and music both feed the
+// master volume.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Channel {Master, Sfx, Music}
+
+impl Channel {
+  fn default_volume(self) -> f32 {
+    match self {
+      Channel::Master => 1.0,
+      Channel::Sfx    => 0.75,
+      Channel::Music  => 0.5
+    }
+  }
+  fn storage_key(self) -> &'static str {
+    match self {
+      Channel::Master => "masterVolume",
+      Channel::Sfx    => "sfxVolume",
+      Channel::Music  => "musicVolume"
+    }
+  }
+}
+
+// Plays decoded sounds through a Web Audio graph. Every play gets its own
+// source node, so sounds overlap instead of cutting each other off. Sounds
+// are fetched in the background and stay silent until they are decoded.
+pub struct AudioEngine {
+  context:AudioContext,
+  master:GainNode,
+  sfx:GainNode,
+  music:GainNode,
+  tracks:Music,
+  buffers:Rc<RefCell<[Option<AudioBuffer>;4]>>,
+  storage:Storage, // volumes are saved to
+}
+
+impl AudioEngine {
+  // Sounds are fetched from under assets, a URL ending in a slash.
+  pub fn init(storage:Storage, assets:&str) -> Self {
+    let context = AudioContext::new().unwrap_throw();
+    let master = context.create_gain().unwrap_throw();
+    let sfx = context.create_gain().unwrap_throw();
+    let music = context.create_gain().unwrap_throw();
+    master.connect_with_audio_node(&context.destination()).unwrap_throw();
+    sfx.connect_with_audio_node(&master).unwrap_throw();
+    music.connect_with_audio_node(&master).unwrap_throw();
+    let tracks = Music::init(&context,&music);
+    let audio = AudioEngine {
+      context,
+      master,
+      sfx,
+      music,
+      tracks,
+      buffers: Rc::new(RefCell::new([None,None,None,None])),
+      storage
+    };
+    for channel in [Channel::Master,Channel::Sfx,Channel::Music] {
+      let volume = audio.storage.load(channel.storage_key())
+        .and_then(|volume| volume.parse().ok())
+        .unwrap_or(channel.default_volume());
+      audio.gain(channel).gain().set_value(volume);
+    }
+    for sound in Sound::ALL {
+      let (context,buffers) = (audio.context.clone(),audio.buffers.clone());
+      let src = format!("{assets}{}",sound.path());
+      wasm_bindgen_futures::spawn_local(async move {
+        if let Ok(buffer) = fetch_sound(&context,&src).await {
+          buffers.borrow_mut()[sound as usize] = Some(buffer);
+        }
+      });
+    }
+    audio
+  }
+
+  fn gain(&self, channel:Channel) -> &GainNode {
+    match channel {
+      Channel::Master => &self.master,
+      Channel::Sfx    => &self.sfx,
+      Channel::Music  => &self.music
+    }
+  }
+
+  pub fn volume(&self, channel:Channel) -> f32 {
+    self.gain(channel).gain().value()
+  }
+
+  pub fn set_volume(&self, channel:Channel, volume:f32) {
+    let volume = volume.clamp(0.0,1.0);
+    self.gain(channel).gain().set_value(volume);
+    self.storage.save(channel.storage_key(),&volume.to_string());
+  }
+
+  // Browsers start audio suspended until the page has been interacted with.
+  pub fn resume(&self) {
+    let _ = self.context.resume();
+  }
+
+  // Stops everything for good, the engine makes no more sound after this.
+  pub fn close(&self) {
+    let _ = self.context.close();
+  }
+
+  pub fn play_music(&self, track:Option<Track>, tempo:f32) {
+    self.tracks.play(track,tempo);
+  }
+
+  // Plays a sound panned towards the column x of the board.
+  pub fn play(&self, sound:Sound, x:usize) {
+    let buffers = self.buffers.borrow();
+    let Some(buffer) = &buffers[sound as usize] else {return};
+    let source = self.context.create_buffer_source().unwrap_throw();
+    source.set_buffer(Some(buffer));
+    let panner = self.context.create_stereo_panner().unwrap_throw();
+    let pan = x as f32 / (GRID_W-1) as f32 * 2.0 - 1.0;
+    panner.pan().set_value(pan * PAN_WIDTH);
+    source.connect_with_audio_node(&panner).unwrap_throw()
+      .connect_with_audio_node(&self.sfx).unwrap_throw();
+    source.start().unwrap_throw();
+  }
+}
+
+async fn fetch_sound(context:&AudioContext, src:&str)
+  -> Result<AudioBuffer,JsValue> {
+  let window = web_sys::window().unwrap_throw();
+  let response = JsFuture::from(window.fetch_with_str(src)).await?
+    .dyn_into::<web_sys::Response>()?;
+  let data = JsFuture::from(response.array_buffer()?).await?;
+  JsFuture::from(context.decode_audio_data(&data.dyn_into()?)?).await?
+    .dyn_into()
+}
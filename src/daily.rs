@@ -0,0 +1,150 @@
+use crate::game_logic::{Board,Death};
+use crate::levels::LEVELS;
+use crate::rules::GameRules;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+const MS_PER_DAY:f64 = 86_400_000.0;
+const SEED_TAG:u64 = 1 << 63; // random seeds stay under 2^53, never clash
+
+// Days since 1970-01-01, UTC, so everyone is on the same day at once.
+pub fn today() -> i64 {
+  (now() / MS_PER_DAY).floor() as i64
+}
+
+// The day as YYYY-MM-DD, after Howard Hinnant's civil_from_days.
+pub fn date(day:i64) -> String {
+  let z = day + 719_468;
+  let era = z.div_euclid(146_097);
+  let day_of_era = z.rem_euclid(146_097);
+  let year_of_era = (day_of_era - day_of_era/1460 + day_of_era/36_524
+    - day_of_era/146_096) / 365;
+  let day_of_year = day_of_era
+    - (365*year_of_era + year_of_era/4 - year_of_era/100);
+  let mp = (5*day_of_year + 2) / 153; // months from March
+  let d = day_of_year - (153*mp + 2)/5 + 1;
+  let m = if mp < 10 {mp + 3} else {mp - 9};
+  let y = year_of_era + era*400 + (m <= 2) as i64;
+  format!("{y:04}-{m:02}-{d:02}")
+}
+
+pub fn seed(day:i64) -> u64 {
+  SEED_TAG | day as u64
+}
+
+// Standard rules, on a level that changes from day to day.
+pub fn rules(day:i64) -> GameRules {
+  GameRules {
+    level: day.rem_euclid(LEVELS.len() as i64) as usize,
+    ..GameRules::init()
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyResult {
+  pub date: String,
+  pub score: u32,
+  pub length: usize,
+  pub apples: u32,
+  pub seconds: u32,
+  pub death: Option<String>, // cause, None if the run was abandoned
+}
+
+impl DailyResult {
+  pub fn new(day:i64, board:&Board, death:Option<Death>) -> Self {
+    DailyResult {
+      date: date(day),
+      score: board.query_score(),
+      length: board.query_length(),
+      apples: board.query_apples_eaten(),
+      seconds: board.query_elapsed() as u32,
+      death: death.map(|death| format!("{:?}",death.cause))
+    }
+  }
+
+  // A few lines of plain text for pasting elsewhere.
+  pub fn summary(&self) -> String {
+    format!(
+      "Snake Daily {}\nScore: {}\nLength {} | Apples {} | {}:{:02}\n{}",
+      self.date,
+      self.score,
+      self.length,
+      self.apples,
+      self.seconds / 60,
+      self.seconds % 60,
+      match &self.death {
+        Some(cause) => format!("Died: {cause}"),
+        None        => "Gave up".into()
+      }
+    )
+  }
+}
+
+// The first run of each day, oldest first. Later runs that day are practice.
+pub struct Daily {
+  history: Vec<DailyResult>,
+}
+
+impl Daily {
+  pub const fn init() -> Self {
+    Daily {history: Vec::new()}
+  }
+
+  pub fn from_json(json:&str) -> Self {
+    Daily {history: serde_json::from_str(json).unwrap_or_default()}
+  }
+
+  pub fn to_json(&self) -> String {
+    serde_json::to_string(&self.history).unwrap_or_default()
+  }
+
+  pub fn result(&self, day:i64) -> Option<&DailyResult> {
+    let date = date(day);
+    self.history.iter().find(|result| result.date == date)
+  }
+
+  // Keeps the result if it's the first for its day, returns whether it was.
+  pub fn record(&mut self, result:DailyResult) -> bool {
+    if self.history.iter().any(|r| r.date == result.date) {return false}
+    self.history.push(result);
+    true
+  }
+}
+
+#[wasm_bindgen]
+extern "C" {
+  #[wasm_bindgen(js_namespace = Date, js_name = now)]
+  fn now() -> f64;
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn dates_from_day_numbers() {
+    assert_eq!(date(0),"1970-01-01");
+    assert_eq!(date(11_016),"2000-02-29");
+    assert_eq!(date(20_745),"2026-10-19");
+    assert_eq!(date(-1),"1969-12-31");
+  }
+
+  #[test]
+  fn only_the_first_run_of_a_day_counts() {
+    let day = 20_745;
+    let mut board = Board::seeded(rules(day),seed(day));
+    board.place_snake(5,5,3,crate::game_logic::Direction::Rt);
+    let mut daily = Daily::init();
+    assert!(daily.record(DailyResult::new(day,&board,None)));
+    board.do_game_tick();
+    assert!(!daily.record(DailyResult::new(day,&board,None)));
+    assert!(daily.record(DailyResult::new(day + 1,&board,None)));
+    let loaded = Daily::from_json(&daily.to_json());
+    assert_eq!(loaded.result(day),daily.history.first());
+    assert_eq!(
+      loaded.result(day).unwrap().summary(),
+      "Snake Daily 2026-10-19\nScore: 0\nLength 1 | Apples 0 | 0:00\nGave up"
+    );
+  }
+}
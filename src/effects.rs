@@ -0,0 +1,177 @@
+use crate::game_logic::*;
+use crate::rand;
+use crate::render::{Renderer,Viewport,clear_cell};
+use crate::theme::Theme;
+
+// All times are in milliseconds, distances and speeds in cells.
+const BURST_PARTICLES:usize = 14;
+const BURST_SPEED:f64 = 4.0;         // cells per second
+const PARTICLE_LIFE:f64 = 450.0;
+const PARTICLE_SIZE:f64 = 0.25;      // of a cell, shrinking over its life
+const SHAKE_TIME:f64 = 250.0;
+const SHAKE_DISTANCE:f64 = 0.2;
+const FLASH_TIME:f64 = 180.0;
+const FLASH_ALPHA:f64 = 0.5;
+const DISSOLVE_STEP:f64 = 40.0;      // between segments
+const DISSOLVE_TIME:f64 = 1500.0;    // longest a whole snake takes
+const DISSOLVE_PARTICLES:usize = 4;
+
+struct Particle {
+  x:f64, // centre
+  y:f64,
+  vx:f64,
+  vy:f64,
+  life:f64, // left to live
+  cell:GridCell, // coloured like the inner colour of this cell
+}
+
+// Eye candy drawn over the board in response to game events. Effects only
+// paint over cells, so every cell painted is remembered and handed back to be
+// repainted from the board on the next frame.
+pub struct Effects {
+  pub reduced_motion:bool, // no movement, flashing or dissolving at all
+  particles:Vec<Particle>,
+  shake:f64,   // time left
+  flash:f64,   // time left
+  flashed:bool, // last frame was drawn with the flash over it
+  dissolving:Vec<(usize,usize)>, // body still to dissolve, head first
+  dissolved:Vec<(usize,usize)>,
+  dissolve_step:f64,
+  dissolve_timer:f64,
+  covered:Vec<(usize,usize)>,
+}
+
+impl Effects {
+  pub const fn init() -> Self {
+    Effects {
+      reduced_motion: false,
+      particles: Vec::new(),
+      shake: 0.0,
+      flash: 0.0,
+      flashed: false,
+      dissolving: Vec::new(),
+      dissolved: Vec::new(),
+      dissolve_step: DISSOLVE_STEP,
+      dissolve_timer: 0.0,
+      covered: Vec::new(),
+    }
+  }
+
+  pub fn clear(&mut self) {
+    *self = Effects {reduced_motion: self.reduced_motion, ..Effects::init()}
+  }
+
+  // Nothing is moving and nothing drawn is left to clean up.
+  pub fn is_idle(&self) -> bool {
+    self.particles.is_empty() && self.dissolving.is_empty()
+      && self.shake <= 0.0 && !self.needs_redraw() && self.covered.is_empty()
+  }
+
+  // The flash covers the whole board, so it is drawn (and cleared) with a
+  // full redraw.
+  pub fn needs_redraw(&self) -> bool {
+    self.flash > 0.0 || self.flashed
+  }
+
+  // Cells painted over since the board was last drawn.
+  pub fn take_covered(&mut self) -> Vec<(usize,usize)> {
+    std::mem::take(&mut self.covered)
+  }
+
+  pub fn apple_collected(&mut self, (x,y):(usize,usize)) {
+    self.burst(x,y,GridCell::Apple(0),BURST_PARTICLES);
+  }
+
+  pub fn grace_tick(&mut self) {
+    if self.reduced_motion {return}
+    self.shake = SHAKE_TIME;
+    self.flash = FLASH_TIME;
+  }
+
+  pub fn game_over(&mut self, body:Vec<(usize,usize)>) {
+    if self.reduced_motion {return}
+    self.dissolve_step = DISSOLVE_STEP.min(DISSOLVE_TIME / body.len() as f64);
+    self.dissolve_timer = 0.0;
+    self.dissolving = body;
+    self.dissolving.reverse(); // popped off the end, so head first
+  }
+
+  fn burst(&mut self, x:usize, y:usize, cell:GridCell, count:usize) {
+    if self.reduced_motion {return}
+    for _ in 0..count {
+      let angle = rand::f64(0.0,std::f64::consts::TAU);
+      let speed = rand::f64(0.5,1.0) * BURST_SPEED;
+      self.particles.push(Particle {
+        x: x as f64 + 0.5,
+        y: y as f64 + 0.5,
+        vx: angle.cos() * speed,
+        vy: angle.sin() * speed,
+        life: rand::f64(0.5,1.0) * PARTICLE_LIFE,
+        cell
+      });
+    }
+  }
+
+  // Moves everything on by the given time.
+  pub fn step(&mut self, elapsed:f64) {
+    for particle in &mut self.particles {
+      particle.x += particle.vx * elapsed / 1000.0;
+      particle.y += particle.vy * elapsed / 1000.0;
+      particle.life -= elapsed;
+    }
+    self.particles.retain(|particle| particle.life > 0.0);
+    self.shake = (self.shake - elapsed).max(0.0);
+    self.flash = (self.flash - elapsed).max(0.0);
+    if !self.dissolving.is_empty() {
+      self.dissolve_timer += elapsed;
+      while self.dissolve_timer >= self.dissolve_step {
+        self.dissolve_timer -= self.dissolve_step;
+        let Some((x,y)) = self.dissolving.pop() else {break};
+        self.dissolved.push((x,y));
+        self.burst(x,y,GridCell::Snake(0,Direction::Rt),DISSOLVE_PARTICLES);
+      }
+    }
+  }
+
+  // How far to move the whole board this frame, in CSS pixels.
+  pub fn shake_offset(&self, vp:&Viewport) -> (f64,f64) {
+    if self.shake <= 0.0 {return (0.0,0.0)}
+    let distance = SHAKE_DISTANCE * vp.cell_w * self.shake / SHAKE_TIME;
+    (rand::f64(-distance,distance),rand::f64(-distance,distance))
+  }
+
+  pub fn draw(
+    &mut self,
+    board:&Board,
+    context:&impl Renderer,
+    vp:&Viewport,
+    theme:&Theme
+  ) {
+    let colour = |cell| theme.colour(cell,false,board.query_grace());
+    for &(x,y) in &self.dissolved {
+      clear_cell(board,context,vp,theme,x,y);
+    }
+    for particle in &self.particles {
+      let size = PARTICLE_SIZE * particle.life / PARTICLE_LIFE;
+      let (left,top) = (particle.x - size / 2.0,particle.y - size / 2.0);
+      context.set_fill_style(&colour(particle.cell)[1]);
+      context.fill_rect(left * vp.cell_w,top * vp.cell_h,
+        size * vp.cell_w,size * vp.cell_h);
+      let cells = |from:f64,len:f64,max:usize| {
+        (from.max(0.0) as usize)..((from + len).max(0.0) as usize + 1).min(max)
+      };
+      for y in cells(top,size,GRID_H) {
+        for x in cells(left,size,GRID_W) {
+          if !self.covered.contains(&(x,y)) {self.covered.push((x,y))}
+        }
+      }
+    }
+    if self.flash > 0.0 {
+      context.set_global_alpha(FLASH_ALPHA * self.flash / FLASH_TIME);
+      context.set_fill_style(&theme.colour(GridCell::Wall,false,true)[1]);
+      context.fill_rect(0.0,0.0,vp.width(),vp.height());
+      context.set_global_alpha(1.0);
+    }
+    self.flashed = self.flash > 0.0;
+  }
+}
@@ -1,41 +1,104 @@
 
+use crate::levels::Level;
+use crate::rand::Rng;
+use crate::rules::GameRules;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
 pub const GRID_W:usize = 30;
 pub const GRID_H:usize = (GRID_W/3)*2; //enforces 3:2 aspect ratio for board
-pub const SNAKE_LENGTH_DEFAULT:usize = 4;  // length is one-based, ∴ len > 0
-const SNAKE_LENGTH_GROWTH:usize = 3;
-const SCORE_INCREMENT:u32 = 50;
+const MAX_PORTALS:usize = 10; // one pair per digit in a level layout
+pub const MAX_APPLES:usize = 9;
+const FEAST_MULTIPLIER:usize = 4;
+const APPLE_LIFETIME:usize = 72;  // in ticks, only counts down when expiring
+const COMBO_WINDOW:usize = 24;    // ticks allowed between apples to keep combo
+pub const MAX_COMBO:u32 = 8;
+pub const MAX_GRACE_TICKS:usize = 5;
+const SPAWN_CLEARANCE:usize = 5;  // empty cells ahead of a new snake
+const APPLE_STREAM:u64 = 0xa991e; // keeps apple draws apart from the snake's
 
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Direction{Up,Dn,Lf,Rt}
 
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub enum GridCell{
   #[default] Nothing,
   Snake(usize,Direction),
-  Apple,
-  Wall
+  Apple(usize),
+  Wall,
+  Portal(usize)
+}
+
+// What happens when the snake crosses the edge of the board.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EdgeMode{
+  Wrap,   // reappear on the opposite edge
+  Solid,  // edges collide like walls
+  Mirror  // wrap, but crossing the left/right edges flips vertically
+}
+
+impl EdgeMode {
+  pub fn next(self) -> Self {
+    match self {
+      EdgeMode::Wrap   => EdgeMode::Solid,
+      EdgeMode::Solid  => EdgeMode::Mirror,
+      EdgeMode::Mirror => EdgeMode::Wrap
+    }
+  }
 }
 
 pub enum GameEvent {
   GeneralMovement,
   GraceTick,
   AppleCollected,
-  GameOver
+  GameOver(Death)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeathCause {
+  Wall,
+  Edge,   // a solid edge of the board
+  Portal, // came out of a portal straight into another
+  Body,
+  Tail    // the last segment, which wasn't moving out of the way
+}
+
+// What the snake ran into on the tick that ended the game.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Death {
+  pub cause: DeathCause,
+  pub cell: Option<(usize,usize)>, // None when leaving the board
+  pub head: (usize,usize),
+  pub body_index: Option<usize>    // of the segment hit, head is 0
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 struct Snake {
   len: usize,
   body: [Option<(usize,usize)>;GRID_W*GRID_H]
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Board {
+  rules: GameRules,
+  seed: u64,
+  rng: Rng,           // places the snake, so a seed replays
   cells: [[GridCell;GRID_W];GRID_H],
   snake: Snake,
-  grace_frame: bool,
-  score: u32
+  portals: [Option<[(usize,usize);2]>;MAX_PORTALS],
+  apples: usize,
+  apples_placed: u64, // over the game, numbers the sequence the next is from
+  combo: u32,
+  combo_ticks: usize,
+  grace_used: usize,
+  grace_saves: u32,
+  score: u32,
+  ticks: u32,         // survived
+  apples_eaten: u32,
+  filled: bool,       // no room was left for another apple
+  dirty: Vec<(usize,usize)> // cells changed since last taken by the renderer
 }
 
 
@@ -49,12 +112,25 @@ impl Snake {
 }
 
 impl Board {
-  pub const fn new() -> Board {
+  pub const fn seeded(rules:GameRules, seed:u64) -> Board {
     Board {
+      rules,
+      seed,
+      rng: Rng::new(seed),
       cells: [[GridCell::Nothing;GRID_W];GRID_H],
       snake: Snake::new(),
-      grace_frame: false,
-      score: 0
+      portals: [None;MAX_PORTALS],
+      apples: 0,
+      apples_placed: 0,
+      combo: 1,
+      combo_ticks: COMBO_WINDOW,
+      grace_used: 0,
+      grace_saves: 0,
+      score: 0,
+      ticks: 0,
+      apples_eaten: 0,
+      filled: false,
+      dirty: Vec::new()
     }
   }
 
@@ -64,6 +140,13 @@ impl Board {
 
   fn owrt_cell(&mut self, new_cell:GridCell, x:usize, y:usize) {
     self.cells[y][x] = new_cell;
+    self.dirty.push((x,y));
+  }
+
+  // Every cell that needs repainting since the last call: anything written
+  // to, plus the old head which lost its head colours when the snake moved.
+  pub fn take_dirty_cells(&mut self) -> Vec<(usize,usize)> {
+    std::mem::take(&mut self.dirty)
   }
 
   pub fn generate_walls(&mut self) -> &mut Board {
@@ -76,6 +159,83 @@ impl Board {
     self
   }
 
+  pub fn load_level(&mut self, level:&Level) -> Result<&mut Board,&str> {
+    let mut portals:[Vec<(usize,usize)>;MAX_PORTALS] = Default::default();
+    for (y,row) in level.layout.iter().enumerate() {
+      if row.len() != GRID_W {return Err("Malformed Level")}
+      for (x,c) in row.chars().enumerate() {
+        match c {
+          '.' => {},
+          '#' => self.owrt_cell(GridCell::Wall,x,y),
+          '0'..='9' => {
+            let id = c as usize - '0' as usize;
+            portals[id].push((x,y));
+            self.owrt_cell(GridCell::Portal(id),x,y);
+          },
+          _ => return Err("Malformed Level")
+        }
+      }
+    }
+    for (id,ends) in portals.iter().enumerate() {
+      self.portals[id] = match ends.as_slice() {
+        []      => None,
+        [a,b]   => Some([*a,*b]),
+        _       => return Err("Unpaired Portal")
+      };
+    }
+    Ok(self)
+  }
+
+  fn portal_partner(&self, id:usize, (x,y):(usize,usize)) -> (usize,usize) {
+    match self.portals[id] {
+      Some([a,b]) if a == (x,y) => b,
+      Some([a,_])               => a,
+      None => panic!("Portal {id} at {x},{y} has no partner")
+    }
+  }
+
+  pub fn query_edge_mode(&self) -> EdgeMode {
+    self.rules.edge_mode
+  }
+
+  // Brings a position that stepped off the board back on according to the
+  // edge mode, or None if the edge is solid.
+  fn cross_edge(&self, x:isize, y:isize) -> Option<(usize,usize)> {
+    let off_x = x < 0 || x >= GRID_W as isize;
+    let off_y = y < 0 || y >= GRID_H as isize;
+    match self.rules.edge_mode {
+      EdgeMode::Solid if off_x || off_y => None,
+      EdgeMode::Mirror if off_x => {
+        let (x,y) = wrap_cells(x,y);
+        Some((x,GRID_H-1-y))
+      },
+      _ => Some(wrap_cells(x,y))
+    }
+  }
+
+  // Finds the cell the snake will enter when moving from (x,y) towards dir.
+  // Entering a portal exits from its partner, still facing dir.
+  fn next_cell(&self, (x,y):(usize,usize), dir:Direction)
+    -> Option<(usize,usize)> {
+    let step = |x:usize, y:usize| {
+      let (x,y) = match dir {
+        Direction::Up => (x as isize, y as isize - 1isize),
+        Direction::Dn => (x as isize, y as isize + 1isize),
+        Direction::Lf => (x as isize - 1isize, y as isize),
+        Direction::Rt => (x as isize + 1isize, y as isize),
+      };
+      self.cross_edge(x,y)
+    };
+    let (x1,y1) = step(x,y)?;
+    match self.peek(x1,y1) {
+      GridCell::Portal(id) => {
+        let (px,py) = self.portal_partner(id,(x1,y1));
+        step(px,py) // a portal directly behind a portal acts as a wall
+      },
+      _ => Some((x1,y1))
+    }
+  }
+
   fn catalogue_empty_spaces(&self) -> Vec<(usize,usize)> {
     (0..GRID_H).flat_map(|y| (0..GRID_W).map(move |x| (x,y))).filter(|pos| {
       let (x,y) = *pos;
@@ -88,21 +248,46 @@ impl Board {
     self.snake.body[0] = Some((x,y));
   }
 
+  // Whether the snake could move n cells from (x,y) towards dir without
+  // running into anything.
+  fn is_clear_ahead(&self, (x,y):(usize,usize), dir:Direction, n:usize)
+    -> bool {
+    let mut pos = (x,y);
+    for _ in 0..n {
+      match self.next_cell(pos,dir) {
+        Some((x,y)) if self.peek(x,y) == GridCell::Nothing => pos = (x,y),
+        _ => return false
+      }
+    }
+    true
+  }
+
+  // Spawns the snake on a random empty cell facing the centre of the board,
+  // or failing that any way with room to move before it hits something.
   pub fn generate_snake(&mut self) -> Result<&mut Board,&str>  {
-    let available_locations = self.catalogue_empty_spaces();
-    if available_locations.is_empty() {return Err("Board Full")}
-    let (snake_x,snake_y) =
-      available_locations[crate::rand::usize(0,available_locations.len())];
-    let relative_x = snake_x as f32 - (GRID_W as f32 / 2.0);
-    let relative_y = snake_y as f32 - (GRID_H as f32 / 2.0);
-    let direction;
-    if relative_x.abs() < relative_y.abs() {
-      if relative_y > 0.0      {direction = Direction::Up}
-      else                     {direction = Direction::Dn}
-    } else if relative_x > 0.0 {direction = Direction::Lf}
-    else                       {direction = Direction::Rt}
-    self.spawn_snake(snake_x,snake_y,SNAKE_LENGTH_DEFAULT,direction);
-    Ok(self)
+    let mut available_locations = self.catalogue_empty_spaces();
+    while !available_locations.is_empty() {
+      let (snake_x,snake_y) = available_locations
+        .swap_remove(self.rng.usize(0,available_locations.len()));
+      let relative_x = snake_x as f32 - (GRID_W as f32 / 2.0);
+      let relative_y = snake_y as f32 - (GRID_H as f32 / 2.0);
+      let towards_centre;
+      if relative_x.abs() < relative_y.abs() {
+        if relative_y > 0.0      {towards_centre = Direction::Up}
+        else                     {towards_centre = Direction::Dn}
+      } else if relative_x > 0.0 {towards_centre = Direction::Lf}
+      else                       {towards_centre = Direction::Rt}
+      use Direction::*;
+      let direction = [towards_centre,Up,Dn,Lf,Rt].into_iter().find(|&dir| {
+        self.is_clear_ahead((snake_x,snake_y),dir,SPAWN_CLEARANCE)
+      });
+      if let Some(direction) = direction {
+        let len = self.rules.starting_length;
+        self.spawn_snake(snake_x,snake_y,len,direction);
+        return Ok(self)
+      }
+    }
+    Err("Board Full")
   }
 
   pub fn change_facing_direction(&mut self, direction:Direction) {
@@ -116,31 +301,99 @@ impl Board {
     };
     if self.snake.len > 1 {
       let (neck_x,neck_y) = self.snake.body[1].unwrap();
-      let (next_x,next_y) = match direction {
-        Direction::Up => (head_x as isize, head_y as isize - 1isize),
-        Direction::Dn => (head_x as isize, head_y as isize + 1isize),
-        Direction::Lf => (head_x as isize - 1isize, head_y as isize),
-        Direction::Rt => (head_x as isize + 1isize, head_y as isize),
-      };
-      let (next_x,next_y) = wrap_cells(next_x,next_y);
-       if next_x == neck_x && next_y == neck_y {return;}
+      if self.next_cell((head_x,head_y),direction) == Some((neck_x,neck_y)) {
+        return;
+      }
     }
     self.owrt_cell(GridCell::Snake(stack,direction),head_x,head_y);
   }
 
   fn spawn_apple(&mut self, x:usize, y:usize) {
-    // self.apple = (x,y);
-    self.owrt_cell(GridCell::Apple,x,y);
+    self.owrt_cell(GridCell::Apple(APPLE_LIFETIME),x,y);
+    self.apples += 1;
+  }
+
+  // Fraction of an apple's lifetime left, always 1.0 if apples don't expire.
+  pub fn query_apple_freshness(&self, ticks_left:usize) -> f64 {
+    if !self.rules.expiring_apples {return 1.0}
+    ticks_left as f64 / APPLE_LIFETIME as f64
+  }
+
+  // Counts down every apple, moving those that run out to a new empty space.
+  // If there is no space left to move to, the apple gets a fresh lifetime.
+  fn age_apples(&mut self) {
+    if !self.rules.expiring_apples {return}
+    for (x,y) in (0..GRID_H).flat_map(|y| (0..GRID_W).map(move |x| (x,y))) {
+      match self.peek(x,y) {
+        GridCell::Apple(t) if t > 1 => self.owrt_cell(GridCell::Apple(t-1),x,y),
+        GridCell::Apple(_) => match self.generate_apple() {
+          Ok(_) => {
+            self.owrt_cell(GridCell::Nothing,x,y);
+            self.apples -= 1;
+          },
+          Err(_) => self.owrt_cell(GridCell::Apple(APPLE_LIFETIME),x,y)
+        },
+        _ => {}
+      }
+    }
+  }
+
+  // Moving freely after a grace tick means grace saved the player.
+  fn end_grace(&mut self) {
+    if self.grace_used > 0 {self.grace_saves += 1;}
+    self.grace_used = 0;
   }
 
+  fn tick_combo(&mut self) {
+    self.combo_ticks = self.combo_ticks.saturating_add(1);
+    if self.combo_ticks > COMBO_WINDOW {self.combo = 1;}
+  }
+
+  fn bump_combo(&mut self) {
+    if self.combo_ticks <= COMBO_WINDOW {
+      self.combo = (self.combo+1).min(MAX_COMBO);
+    }
+    self.combo_ticks = 0;
+  }
+
+  pub fn query_apple_target(&self) -> usize {
+    if self.rules.feast {self.rules.apple_target*FEAST_MULTIPLIER}
+    else {self.rules.apple_target}
+  }
+
+  // Tops the board up to its apple target, returning how many were placed.
+  // Running out of space part way is fine, only placing nothing is an error.
+  pub fn fill_apples(&mut self) -> Result<usize,&str> {
+    let mut placed = 0;
+    while self.apples < self.query_apple_target() {
+      match self.generate_apple() {
+        Ok(_) => placed += 1,
+        Err(_) if placed > 0 => break,
+        Err(_) => return Err("Board Full")
+      }
+    }
+    Ok(placed)
+  }
+
+  // Each apple is placed on the first free cell of its own fixed order of
+  // cells, shuffled from the seed. The path taken only changes which cells
+  // are skipped, so everyone playing a seed is given the same apples.
   pub fn generate_apple(&mut self) -> Result<&mut Board,&str> {
-    let available_locations = self.catalogue_empty_spaces();
-    if available_locations.len() == 0 {return Err("Board Full")}
-    let (apple_x,apple_y) =
-      available_locations[crate::rand::usize(0,available_locations.len())];
-    // self.apple = (apple_x,apple_y);
-    self.spawn_apple(apple_x,apple_y);
-    Ok(self)
+    let mut rng = Rng::stream(self.seed ^ APPLE_STREAM,self.apples_placed);
+    let mut cells = (0..GRID_H)
+      .flat_map(|y| (0..GRID_W).map(move |x| (x,y)))
+      .collect::<Vec<(usize,usize)>>();
+    for i in 0..cells.len() { // shuffled only as far as needed
+      let j = rng.usize(i,cells.len());
+      cells.swap(i,j);
+      let (apple_x,apple_y) = cells[i];
+      if self.peek(apple_x,apple_y) == GridCell::Nothing {
+        self.apples_placed += 1;
+        self.spawn_apple(apple_x,apple_y);
+        return Ok(self)
+      }
+    }
+    Err("Board Full")
   }
 
   fn swap_cells(&mut self,(x0,y0):(usize,usize),(x1,y1):(usize,usize)) {
@@ -150,64 +403,76 @@ impl Board {
   }
 
   pub fn do_game_tick(&mut self) -> GameEvent {
-    let game_event; 
+    let game_event = self.advance();
+    if !matches!(game_event,GameEvent::GameOver(_)) {self.ticks += 1;}
+    game_event
+  }
+
+  fn advance(&mut self) -> GameEvent {
+    self.age_apples();
+    self.tick_combo();
     //Look ahead
     let (x0,y0) = self.snake.body[0].unwrap();
-    let (x1,y1,d) = match self.peek(x0,y0) {
-      GridCell::Snake(_,Direction::Up) =>
-        (x0 as isize, y0 as isize - 1isize, Direction::Up),
-      GridCell::Snake(_,Direction::Dn) =>
-        (x0 as isize, y0 as isize + 1isize, Direction::Dn),
-      GridCell::Snake(_,Direction::Lf) =>
-        (x0 as isize - 1isize, y0 as isize, Direction::Lf),
-      GridCell::Snake(_,Direction::Rt) =>
-        (x0 as isize + 1isize, y0 as isize, Direction::Rt),
+    let d = match self.peek(x0,y0) {
+      GridCell::Snake(_,d) => d,
       _ => panic!("Snake head not found at {x0}, {y0}")
     };
- 
+    let Some((x1,y1)) = self.next_cell((x0,y0),d) else {
+      return self.collide(DeathCause::Edge,None);
+    };
+
     //Handle Collisions
-    let (x1,y1) = wrap_cells(x1,y1);
-    match self.peek(x1,y1) {
+    let game_event = match self.peek(x1,y1) {
       GridCell::Nothing    => {
-        self.grace_frame = false;
-        game_event = GameEvent::GeneralMovement;
+        self.end_grace();
+        GameEvent::GeneralMovement
       },
-      GridCell::Apple      => {
-        self.grace_frame = false;
+      GridCell::Apple(_)   => {
+        self.end_grace();
+        self.apples -= 1;
+        self.apples_eaten += 1;
+        self.bump_combo();
         let (x,y) = self.snake.body[self.snake.len-1].unwrap();
         match self.peek(x,y) {
           GridCell::Snake(s,d) => {self.owrt_cell(
-              GridCell::Snake(s+SNAKE_LENGTH_GROWTH,d),x,y);}
+              GridCell::Snake(s+self.rules.growth_per_apple,d),x,y);}
           _ => panic!("Attemted to add to stack of non-snake at {x},{y}")
         };
-        match self.generate_apple() {
-          Ok(_) => self.increase_score(1),
-          Err("Board Full") => self.increase_score(50),
+        match self.fill_apples() {
+          Ok(_) => {},
+          Err("Board Full") => if !self.filled { // only as it fills up
+            self.filled = true;
+            self.score += self.rules.full_board_bonus;
+          },
           Err(e) => panic!("An unknown error has occured:\n{e}"),
         }
-        game_event = GameEvent::AppleCollected;
+        self.increase_score(self.combo);
+        GameEvent::AppleCollected
       },
       GridCell::Snake(0,_) if self.snake.body[self.snake.len-1] == Some((x1,y1))
         => {
-      self.grace_frame = false;
+      self.end_grace();
       self.swap_cells(self.snake.body[0].unwrap(),
         self.snake.body[self.snake.len-1].unwrap());
       self.snake.body[0..self.snake.len].rotate_right(1);
-      game_event = GameEvent::GeneralMovement;
-      return game_event;
+      // The old head now holds the tail's cell, point it at the new head.
+      if let GridCell::Snake(s,_) = self.peek(x0,y0) {
+        self.owrt_cell(GridCell::Snake(s,d),x0,y0);
+      }
+      return GameEvent::GeneralMovement;
     }
-      GridCell::Snake(_,_)  |
+      GridCell::Snake(_,_)  => {
+        let is_tail = self.snake.body[self.snake.len-1] == Some((x1,y1));
+        let cause = if is_tail {DeathCause::Tail} else {DeathCause::Body};
+        return self.collide(cause,Some((x1,y1)));
+      },
       GridCell::Wall        => {
-        if !self.grace_frame {
-          self.grace_frame = true;
-          game_event = GameEvent::GraceTick;
-          return game_event;
-        } else {
-          game_event = GameEvent::GameOver;
-          return game_event;
-        }
+        return self.collide(DeathCause::Wall,Some((x1,y1)));
       },
-    }
+      GridCell::Portal(_)   => {
+        return self.collide(DeathCause::Portal,Some((x1,y1)));
+      },
+    };
 
     //Move Snake
     self.snake.body[0..=self.snake.len].rotate_right(1);
@@ -224,23 +489,108 @@ impl Board {
     }
     self.owrt_cell(GridCell::Snake(0,d),x1,y1);
     self.snake.body[0] = Some((x1,y1));
+    self.dirty.push((x0,y0));
     game_event
   }
 
+  fn collide(&mut self, cause:DeathCause, cell:Option<(usize,usize)>)
+    -> GameEvent {
+    let head = self.snake.body[0].unwrap();
+    self.dirty.push(head);
+    if self.grace_used < self.rules.grace_ticks {
+      self.grace_used += 1;
+      GameEvent::GraceTick
+    } else {
+      let body_index = match cell {
+        Some(cell) if matches!(cause,DeathCause::Body|DeathCause::Tail) => {
+          self.query_body().position(|segment| segment == cell)
+        },
+        _ => None
+      };
+      GameEvent::GameOver(Death {cause,cell,head,body_index})
+    }
+  }
+
+  pub fn query_rules(&self) -> GameRules {
+    self.rules
+  }
+
+  pub fn query_seed(&self) -> u64 {
+    self.seed
+  }
+
   pub fn query_score(&self) -> u32 {
     self.score
   }
 
+  pub fn query_combo(&self) -> u32 {
+    self.combo
+  }
+
   pub fn query_grace(&self) -> bool {
-    self.grace_frame
+    self.grace_used > 0
+  }
+
+  pub fn query_grace_remaining(&self) -> usize {
+    self.rules.grace_ticks - self.grace_used
+  }
+
+  pub fn query_grace_saves(&self) -> u32 {
+    self.grace_saves
+  }
+
+  pub fn query_ticks(&self) -> u32 {
+    self.ticks
+  }
+
+  // Time played, in seconds of game time so pauses don't count.
+  pub fn query_elapsed(&self) -> f32 {
+    self.ticks as f32 / self.rules.tick_rate
+  }
+
+  pub fn query_apples_eaten(&self) -> u32 {
+    self.apples_eaten
+  }
+
+  pub fn query_filled(&self) -> bool {
+    self.filled
+  }
+
+  pub fn query_length(&self) -> usize {
+    self.snake.len
   }
 
   pub fn query_head_location(&self) -> Option<(usize,usize)> {
     self.snake.body[0]
   }
 
+  // The snake's cells in order from head to tail.
+  pub fn query_body(&self) -> impl Iterator<Item=(usize,usize)> + '_ {
+    self.snake.body[..self.snake.len].iter().flatten().copied()
+  }
+
   fn increase_score(&mut self,increase:u32) {
-    self.score += increase*SCORE_INCREMENT;
+    self.score += increase*self.rules.score_per_apple;
+  }
+}
+
+// Puts things exactly where a test needs them, rather than where the seed
+// would.
+#[cfg(test)]
+impl Board {
+  pub const fn new(rules:GameRules) -> Board {
+    Board::seeded(rules,0)
+  }
+
+  pub fn place_snake(&mut self, x:usize, y:usize, len:usize, dir:Direction)
+    -> &mut Board {
+    self.spawn_snake(x,y,len,dir);
+    self
+  }
+
+  pub fn place_apple(&mut self, x:usize, y:usize) -> &mut Board {
+    self.spawn_apple(x,y);
+    self
   }
 }
 
@@ -252,4 +602,254 @@ fn wrap_cells(x:isize, y:isize) -> (usize,usize) {
     else if y < 0isize {GRID_H-1}
     else {y as usize};
   (x,y)
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::levels::LEVELS;
+
+  fn board(rules:GameRules) -> Board {
+    Board::new(GameRules {grace_ticks: 0, ..rules})
+  }
+
+  fn death(event:GameEvent) -> Death {
+    match event {
+      GameEvent::GameOver(death) => death,
+      _ => panic!("Expected the game to be over")
+    }
+  }
+
+  // Ticks the snake along each direction in turn, returning the last event.
+  fn steer(board:&mut Board, moves:&[Direction]) -> GameEvent {
+    let mut event = GameEvent::GeneralMovement;
+    for &dir in moves {
+      board.change_facing_direction(dir);
+      event = board.do_game_tick();
+    }
+    event
+  }
+
+  #[test]
+  fn same_seed_lays_out_the_same_game() {
+    let rules = GameRules {apple_target: 3, ..GameRules::init()};
+    let lay_out = |seed| {
+      let mut board = Board::seeded(rules,seed);
+      board.generate_walls().generate_snake().unwrap();
+      board.fill_apples().unwrap();
+      steer(&mut board,&[Direction::Up,Direction::Up]);
+      board
+    };
+    assert_eq!(lay_out(2026),lay_out(2026));
+    assert_ne!(lay_out(2026),lay_out(2027));
+  }
+
+  #[test]
+  fn apples_follow_the_seed_not_the_path() {
+    use Direction::*;
+    let rules = GameRules {apple_target: 3, ..GameRules::init()};
+    let apples_after = |moves:&[Direction]| {
+      let mut board = Board::seeded(rules,2026);
+      board.place_snake(15,10,4,Up);
+      steer(&mut board,moves);
+      board.fill_apples().unwrap();
+      apples(&board)
+    };
+    assert_eq!(apples_after(&[Up,Up,Lf,Lf]),apples_after(&[Rt,Dn,Dn,Dn]));
+  }
+
+  #[test]
+  fn wall_death() {
+    let mut board = board(GameRules::init());
+    board.generate_walls().place_snake(1,5,1,Direction::Lf);
+    assert_eq!(death(board.do_game_tick()),Death {
+      cause: DeathCause::Wall,
+      cell: Some((0,5)),
+      head: (1,5),
+      body_index: None
+    });
+  }
+
+  #[test]
+  fn edge_death() {
+    let mut board = board(GameRules {
+      edge_mode: EdgeMode::Solid,
+      ..GameRules::init()
+    });
+    board.place_snake(0,5,1,Direction::Lf);
+    let death = death(board.do_game_tick());
+    assert_eq!((death.cause,death.cell),(DeathCause::Edge,None));
+  }
+
+  #[test]
+  fn no_grace_dies_on_the_first_collision() {
+    let mut board = Board::new(GameRules {grace_ticks: 0, ..GameRules::init()});
+    board.generate_walls().place_snake(1,5,1,Direction::Lf);
+    assert!(matches!(board.do_game_tick(),GameEvent::GameOver(_)));
+  }
+
+  #[test]
+  fn grace_lasts_its_whole_budget() {
+    let mut board = Board::new(GameRules {grace_ticks: 3, ..GameRules::init()});
+    board.generate_walls().place_snake(1,5,1,Direction::Lf);
+    for remaining in (0..3).rev() {
+      assert!(matches!(board.do_game_tick(),GameEvent::GraceTick));
+      assert_eq!(board.query_grace_remaining(),remaining);
+    }
+    assert!(matches!(board.do_game_tick(),GameEvent::GameOver(_)));
+    assert_eq!(board.query_grace_saves(),0);
+  }
+
+  #[test]
+  fn grace_is_only_a_save_when_steered_out() {
+    let mut board = Board::new(GameRules {grace_ticks: 2, ..GameRules::init()});
+    board.generate_walls().place_snake(1,5,1,Direction::Lf);
+    board.do_game_tick();
+    board.do_game_tick();
+    assert_eq!(board.query_grace_saves(),0);
+    assert!(matches!(steer(&mut board,&[Direction::Up]),
+      GameEvent::GeneralMovement));
+    assert_eq!(board.query_grace_saves(),1);
+    assert!(!board.query_grace());
+    board.do_game_tick();
+    assert_eq!(board.query_grace_saves(),1);
+  }
+
+  #[test]
+  fn mirror_edges_flip_across_the_sides_only() {
+    let mirror = GameRules {edge_mode: EdgeMode::Mirror, ..GameRules::init()};
+    let head_after = |x,y,dir| {
+      let mut board = board(mirror);
+      board.place_snake(x,y,1,dir).do_game_tick();
+      board.query_head_location().unwrap()
+    };
+    let y = 3;
+    assert_eq!(head_after(GRID_W-1,y,Direction::Rt),(0,GRID_H-1-y));
+    assert_eq!(head_after(0,y,Direction::Lf),(GRID_W-1,GRID_H-1-y));
+    // The top and bottom edges wrap as usual.
+    assert_eq!(head_after(7,0,Direction::Up),(7,GRID_H-1));
+    assert_eq!(head_after(7,GRID_H-1,Direction::Dn),(7,0));
+  }
+
+  #[test]
+  fn body_death_reports_segment() {
+    use Direction::*;
+    let mut board = board(GameRules::init());
+    board.place_snake(5,5,5,Rt);
+    let death = death(steer(&mut board,&[Rt,Rt,Rt,Rt,Dn,Lf,Up]));
+    assert_eq!(death.cause,DeathCause::Body);
+    assert_eq!((death.cell,death.head),(Some((8,5)),(8,6)));
+    assert_eq!(death.body_index,Some(3));
+  }
+
+  #[test]
+  fn tail_still_growing_is_hit() {
+    use Direction::*;
+    let mut board = board(GameRules::init());
+    board.place_snake(5,5,5,Rt);
+    let death = death(steer(&mut board,&[Rt,Dn,Lf,Up]));
+    assert_eq!(death.cause,DeathCause::Tail);
+    assert_eq!(death.body_index,Some(3));
+  }
+
+  #[test]
+  fn snake_spawns_with_room_to_move() {
+    let rules = GameRules {level: 2, grace_ticks: 0, ..GameRules::init()};
+    for seed in 0..500 {
+      let mut board = Board::seeded(rules,seed);
+      board.load_level(&LEVELS[rules.level]).unwrap();
+      board.generate_walls().generate_snake().unwrap();
+      for _ in 0..SPAWN_CLEARANCE {
+        let event = board.do_game_tick();
+        assert!(!matches!(event,GameEvent::GameOver(_)),"seed {seed}");
+      }
+    }
+  }
+
+  #[test]
+  fn portals_keep_the_direction_of_travel() {
+    let mut board = board(GameRules::init());
+    board.load_level(&LEVELS[1]).unwrap().place_snake(2,2,2,Direction::Rt);
+    board.do_game_tick();
+    assert_eq!(board.query_head_location(),Some((27,17)));
+    assert_eq!(board.peek(27,17),GridCell::Snake(0,Direction::Rt));
+    // Back through the portal is where the neck is, so can't be turned to.
+    board.change_facing_direction(Direction::Lf);
+    assert_eq!(board.peek(27,17),GridCell::Snake(0,Direction::Rt));
+  }
+
+  #[test]
+  fn portals_must_be_paired() {
+    let mut layout = LEVELS[0].layout;
+    layout[3] = "...0..........................";
+    let mut board = board(GameRules::init());
+    assert_eq!(board.load_level(&Level {layout}).err(),Some("Unpaired Portal"));
+  }
+
+  // Every apple on the board, in reading order.
+  fn apples(board:&Board) -> Vec<(usize,usize)> {
+    (0..GRID_H).flat_map(|y| (0..GRID_W).map(move |x| (x,y)))
+      .filter(|&(x,y)| matches!(board.peek(x,y),GridCell::Apple(_)))
+      .collect()
+  }
+
+  #[test]
+  fn expired_apple_moves_elsewhere() {
+    let mut board = board(GameRules {
+      expiring_apples: true,
+      ..GameRules::init()
+    });
+    board.place_snake(5,5,1,Direction::Rt).place_apple(20,15);
+    for _ in 1..APPLE_LIFETIME {board.do_game_tick();}
+    assert_eq!(board.peek(20,15),GridCell::Apple(1));
+    board.do_game_tick();
+    let moved = apples(&board);
+    assert_eq!(moved.len(),1);
+    assert_ne!(moved[0],(20,15));
+    assert_eq!(board.apples,moved.len());
+  }
+
+  #[test]
+  fn combo_grows_on_consecutive_apples_and_lapses() {
+    let mut board = board(GameRules {apple_target: 0, ..GameRules::init()});
+    board.place_snake(5,5,1,Direction::Rt).place_apple(6,5).place_apple(7,5);
+    board.do_game_tick();
+    assert_eq!(board.query_combo(),1);
+    board.do_game_tick();
+    assert_eq!(board.query_combo(),2);
+    for _ in 0..COMBO_WINDOW {board.do_game_tick();}
+    assert_eq!(board.query_combo(),2);
+    board.do_game_tick();
+    assert_eq!(board.query_combo(),1);
+  }
+
+  #[test]
+  fn each_combo_step_scores_more_up_to_the_cap() {
+    let rules = GameRules {apple_target: 0, ..GameRules::init()};
+    let mut board = board(rules);
+    board.place_snake(1,5,1,Direction::Rt);
+    let steps = MAX_COMBO as usize + 2;
+    for x in 2..2+steps {board.place_apple(x,5);}
+    for step in 1..=steps as u32 {
+      let score = board.query_score();
+      board.do_game_tick();
+      let multiplier = step.min(MAX_COMBO);
+      assert_eq!(board.query_score() - score,multiplier*rules.score_per_apple);
+    }
+  }
+
+  #[test]
+  fn full_board_bonus_is_only_awarded_once() {
+    let rules = GameRules {apple_target: 3, ..GameRules::init()};
+    let mut board = board(rules);
+    for (x,y) in (0..GRID_H).flat_map(|y| (0..GRID_W).map(move |x| (x,y))) {
+      if y != 5 || x > 4 {board.owrt_cell(GridCell::Wall,x,y)}
+    }
+    board.place_snake(1,5,1,Direction::Rt);
+    for x in 2..=4 {board.place_apple(x,5);}
+    board.do_game_tick();
+    board.do_game_tick();
+    assert!(board.query_filled());
+    let apples = rules.score_per_apple * (1 + 2); // second one is a combo
+    assert_eq!(board.query_score(),rules.full_board_bonus + apples);
+  }
+}
@@ -0,0 +1,145 @@
+use crate::game_logic::{Board,Direction,GameEvent};
+use serde::{Deserialize, Serialize};
+
+const MAX_RUNS:usize = 16; // seeds with a best run kept, most recent first
+
+// A finished game, kept so it can be raced. A board plays out the same from
+// the same seed and rules, so only the turns made along the way are needed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Run {
+  pub seed: u64,
+  pub bucket: u32,                  // of the rules, see GameRules::bucket
+  pub score: u32,
+  pub turns: Vec<(u32,Direction)>,  // (ticks done when made, direction)
+}
+
+impl Run {
+  pub const fn init() -> Self {
+    Run {seed: 0, bucket: 0, score: 0, turns: Vec::new()}
+  }
+
+  pub fn new(board:&Board) -> Self {
+    Run {
+      seed: board.query_seed(),
+      bucket: board.query_rules().bucket(),
+      ..Run::init()
+    }
+  }
+}
+
+// The best run of each recent seed, kept in storage as JSON.
+pub struct BestRuns {
+  runs:Vec<Run>,
+}
+
+impl BestRuns {
+  pub const fn init() -> Self {
+    BestRuns {runs: Vec::new()}
+  }
+
+  pub fn from_json(json:&str) -> Self {
+    BestRuns {runs: serde_json::from_str(json).unwrap_or_default()}
+  }
+
+  pub fn to_json(&self) -> String {
+    serde_json::to_string(&self.runs).unwrap_or_default()
+  }
+
+  pub fn best(&self, board:&Board) -> Option<&Run> {
+    let bucket = board.query_rules().bucket();
+    self.runs.iter()
+      .find(|run| run.seed == board.query_seed() && run.bucket == bucket)
+  }
+
+  // Keeps the run if it beats the best of its seed, returns whether it did.
+  pub fn submit(&mut self, run:Run) -> bool {
+    let same = |r:&Run| r.seed == run.seed && r.bucket == run.bucket;
+    if let Some(i) = self.runs.iter().position(same) {
+      if self.runs[i].score >= run.score {return false}
+      self.runs.remove(i);
+    }
+    self.runs.insert(0,run);
+    self.runs.truncate(MAX_RUNS);
+    true
+  }
+}
+
+// Plays a run back on its own board alongside the live game, a tick at a
+// time, so it can't collide with or otherwise touch the live board.
+pub struct Ghost {
+  board:Board,
+  turns:Vec<(u32,Direction)>,
+  next:usize,   // turn to make next
+  over:bool,
+}
+
+impl Ghost {
+  // The board has to be laid out just as the run's was when it started.
+  pub fn new(board:Board, run:&Run) -> Self {
+    Ghost {board, turns: run.turns.clone(), next: 0, over: false}
+  }
+
+  pub fn tick(&mut self) {
+    if self.over {return}
+    while let Some(&(tick,dir)) = self.turns.get(self.next) {
+      if tick > self.board.query_ticks() {break}
+      self.board.change_facing_direction(dir);
+      self.next += 1;
+    }
+    if let GameEvent::GameOver(_) = self.board.do_game_tick() {
+      self.over = true;
+    }
+    self.board.take_dirty_cells(); // always drawn in full
+  }
+
+  // The board to draw, until the run has ended.
+  pub fn board(&self) -> Option<&Board> {
+    if self.over {None} else {Some(&self.board)}
+  }
+
+  pub fn score(&self) -> u32 {
+    self.board.query_score()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::rules::GameRules;
+
+  fn start(seed:u64) -> Board {
+    let rules = GameRules {
+      apple_target: 5,
+      build_walls: false,
+      ..GameRules::init()
+    };
+    let mut board = Board::seeded(rules,seed);
+    board.generate_snake().unwrap();
+    board.fill_apples().unwrap();
+    board
+  }
+
+  #[test]
+  fn ghost_follows_the_recorded_path() {
+    let mut live = start(7);
+    let mut run = Run::new(&live);
+    for &dir in [Direction::Up,Direction::Rt].iter().cycle().take(12) {
+      live.change_facing_direction(dir);
+      run.turns.push((live.query_ticks(),dir));
+      for _ in 0..2 {
+        let event = live.do_game_tick();
+        assert!(!matches!(event,GameEvent::GameOver(_)));
+      }
+    }
+    run.score = live.query_score();
+    let mut best = BestRuns::init();
+    assert!(best.submit(run.clone()));
+    assert!(!best.submit(run));
+    assert!(best.best(&start(8)).is_none());
+    let run = best.best(&start(7)).expect("Expected a best run for the seed");
+    let mut ghost = Ghost::new(start(7),run);
+    for _ in 0..live.query_ticks() {ghost.tick();}
+    live.take_dirty_cells();
+    assert_eq!(ghost.board(),Some(&live));
+  }
+}
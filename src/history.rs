@@ -0,0 +1,55 @@
+use crate::game_logic::Board;
+use std::collections::VecDeque;
+
+const MAX_STATES:usize = 300; // 25 seconds at the standard tick rate
+
+// The boards from before the last few hundred ticks, newest last, for
+// stepping a practice game back in time. Oldest states drop off the front.
+pub struct History {
+  states:VecDeque<Board>,
+}
+
+impl History {
+  pub const fn init() -> Self {
+    History {states: VecDeque::new()}
+  }
+
+  pub fn push(&mut self, board:&Board) {
+    if self.states.len() == MAX_STATES {self.states.pop_front();}
+    self.states.push_back(board.clone());
+  }
+
+  // The board from one tick further back, if there is one.
+  pub fn pop(&mut self) -> Option<Board> {
+    self.states.pop_back()
+  }
+
+  pub fn clear(&mut self) {
+    self.states.clear();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::game_logic::Direction;
+  use crate::rules::GameRules;
+
+  #[test]
+  fn steps_back_through_the_most_recent_ticks() {
+    let rules = GameRules {build_walls: false, ..GameRules::init()};
+    let mut board = Board::new(rules);
+    board.place_snake(5,5,3,Direction::Rt);
+    let mut history = History::init();
+    for _ in 0..MAX_STATES + 10 {
+      history.push(&board);
+      board.do_game_tick();
+    }
+    let ticks = board.query_ticks();
+    assert_eq!(history.pop().unwrap().query_ticks(),ticks - 1);
+    assert_eq!(history.pop().unwrap().query_ticks(),ticks - 2);
+    let mut oldest = None;
+    while let Some(board) = history.pop() {oldest = Some(board)}
+    assert_eq!(oldest.unwrap().query_ticks(),10);
+  }
+}
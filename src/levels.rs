@@ -0,0 +1,82 @@
+use crate::game_logic::GRID_H;
+
+// Layouts are GRID_H rows of GRID_W characters:
+//   '.' empty, '#' wall, '0'..='9' portal (each digit used exactly twice)
+pub struct Level {
+  pub layout: [&'static str;GRID_H]
+}
+
+pub const LEVELS:[Level;3] = [
+  Level {
+    layout: [
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+    ]
+  },
+  Level {
+    layout: [
+      "..............................",
+      "..............................",
+      "...0......................1...",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "...1......................0...",
+      "..............................",
+      "..............................",
+    ]
+  },
+  Level {
+    layout: [
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "......########....########....",
+      "......#..................#....",
+      "......#........0.........#....",
+      "......#..................#....",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+      "......#..................#....",
+      "......#........0.........#....",
+      "......#..................#....",
+      "......########....########....",
+      "..............................",
+      "..............................",
+      "..............................",
+      "..............................",
+    ]
+  },
+];
@@ -1,24 +1,54 @@
+mod achievements;
+mod audio;
+mod daily;
+mod effects;
 mod game_logic;
+mod ghost;
+mod history;
+mod levels;
+mod menu;
+mod music;
 mod rand;
+mod render;
+mod rules;
+mod stats;
+mod storage;
+mod theme;
+use achievements::{Achievement,Achievements};
+use audio::{AudioEngine,Channel,Sound};
+use daily::{Daily,DailyResult};
+use effects::Effects;
 use game_logic::*;
+use ghost::{BestRuns,Ghost,Run};
+use history::History;
+use levels::LEVELS;
+use menu::{Action,Menu,Screen,Setting};
+use music::Track;
+use render::{
+  Line,Viewport,draw_board,draw_board_original,draw_cells,draw_menu
+};
+use rules::GameRules;
+use stats::Stats;
+use storage::Storage;
+use theme::Theme;
+use std::{cell::Cell, rc::Rc};
 use wasm_bindgen::{prelude::*, JsCast};
 
-const TPS:f32 = 12.0; //game board is ticked (snake moves) on every tick
-const CANV_W:u32 = 1200;
-const CANV_H:u32 = (CANV_W/3)*2;     //enforces 3:2 aspect ratio for window
-const CELL_W:u32 = CANV_W/GRID_W as u32;
-const CELL_H:u32 = CANV_H/GRID_H as u32;
-const SFX_VOL:f64 = 0.75;
+const MIN_CELL_SIZE:f64 = 8.0;      // in CSS pixels
+const MAX_HEIGHT_FRACTION:f64 = 0.8; // of the window, leaves room for the page
+const MAX_FRAME_TIME:f64 = 100.0;    // in ms, caps effect steps after a stall
+const TOAST_TIME:i32 = 4000;         // in ms
+// How a toast made for a page without one looks, it's shown and hidden with
+// its opacity.
+const TOAST_STYLE:&str = "position: fixed; top: 12pt; right: 12pt; \
+  padding: 8pt 12pt; border-radius: 4pt; background: #282828; \
+  color: #ebdbb2; font-family: monospace; pointer-events: none; \
+  transition: opacity 0.4s;";
 
 
 // #[global_allocator]
 // static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
-static mut GAME_STATE:GameState = GameState::init();
-
-static mut PAGE_ELEMS:core::mem::MaybeUninit::<PageElements> =
-  core::mem::MaybeUninit::<PageElements>::uninit();
-
 // macro_rules! console_log {
 //   ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
 // }
@@ -31,330 +61,1098 @@ static mut PAGE_ELEMS:core::mem::MaybeUninit::<PageElements> =
 
 enum BkColour {Dark,Light}
 
+#[wasm_bindgen]
+extern "C" {
+  // Where this module was loaded from.
+  #[wasm_bindgen(
+    thread_local_v2,
+    js_namespace = ["import", "meta"],
+    js_name = url
+  )]
+  static MODULE_URL: String;
+}
+
+// Ids of the elements a game is drawn into, where it saves and where its
+// sounds and backgrounds are loaded from. Any elements that aren't found
+// inside the container are made and added to the end of it.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MountOptions {
+  pub canvas: String,
+  pub score: String,
+  pub combo: String,
+  #[wasm_bindgen(js_name = highScore)]
+  pub high_score: String,
+  pub hud: String,
+  pub toast: String,
+  pub namespace: String, // of the saved progress and settings, see Storage
+  pub assets: String,    // URL of the directory holding sounds/, backgrounds/
+}
+
+#[wasm_bindgen]
+impl MountOptions {
+  // The ids used by index.html, and the assets directory beside the one this
+  // module is in, as they're laid out in the repo.
+  #[wasm_bindgen(constructor)]
+  pub fn standard() -> Self {
+    MountOptions {
+      canvas: "canvas".into(),
+      score: "score".into(),
+      combo: "combo".into(),
+      high_score: "highscore".into(),
+      hud: "hud".into(),
+      toast: "toast".into(),
+      namespace: String::new(),
+      assets: MODULE_URL
+        .with(|url| web_sys::Url::new_with_base("../assets/",url))
+        .map_or("./assets/".into(),|url| url.href()),
+    }
+  }
+}
+
+impl MountOptions {
+  // The assets URL with a slash on the end, for paths to go straight after.
+  fn assets_dir(&self) -> String {
+    if self.assets.is_empty() || self.assets.ends_with('/') {
+      self.assets.clone()
+    } else {
+      format!("{}/",self.assets)
+    }
+  }
+}
+
 struct PageElements {
   canvas:web_sys::HtmlCanvasElement,
   context:web_sys::CanvasRenderingContext2d,
   score:web_sys::HtmlElement,
+  combo:web_sys::HtmlElement,
   high_score:web_sys::HtmlElement,
-  body:web_sys::HtmlBodyElement,
-  sound_effects:SoundEffectElements
+  hud:web_sys::HtmlElement,
+  toast:web_sys::HtmlElement,
+  toasts_shown:Rc<Cell<u32>>, // so only the last shown hides it
+  container:web_sys::HtmlElement, // takes the theme's background
+  audio:AudioEngine
 }
 
-struct SoundEffectElements {
-  apple:web_sys::HtmlMediaElement,
-  grace:web_sys::HtmlMediaElement,
-  game_over:web_sys::HtmlMediaElement
-}
-
-struct GameState {
+// One game, drawn into its own set of page elements. JS drives it: it calls
+// start once, then tick at the tick rate and animate every frame until it is
+// done with the game, when destroy frees it.
+#[wasm_bindgen]
+pub struct Game {
+  page:PageElements,
+  storage:Storage,
+  assets:String, // where sounds and backgrounds are, ends in a slash
   board:Board,
-  high_score:[u32;2],
-  use_alt_high_score:usize, // usize to index into high_score,
-  is_game_over:bool,        // acts as bool otherwise
+  rules:GameRules,            // applied to the board on the next reset
+  high_scores:Vec<(u32,u32)>, // (rules bucket, high score)
+  is_game_over:bool,
   is_game_paused:bool,
-  should_build_walls:bool,
   should_mute_sfx:bool,
+  should_mute_music:bool,
+  movement_ticks:bool, // play a sound on every move
+  themes:Vec<Theme>,  // built-ins first, then imported themes
+  theme:usize,
+  use_shapes:bool,    // accessibility mode, draws shapes as well as colours
+  viewport:Viewport,
+  drawn_with_grace:bool, // palette of the last frame, changing it redraws all
+  drawn_with_ghost:bool, // the last frame has a ghost, to be drawn over
+  effects:Effects,
+  last_frame:f64,     // animation frame timestamp, in ms
+  menu:Menu,
+  death:Option<Death>, // how the last game ended
+  stats:Stats,
+  achievements:Achievements,
+  daily:Daily,
+  daily_day:Option<i64>, // being played, None outside the daily challenge
+  daily_counted:bool,    // this run was the one kept for its day
+  best_runs:BestRuns,
+  run:Run,              // the turns of this game so far
+  ghost:Option<Ghost>,  // being raced
+  next_seed:Option<u64>, // for the next board, rather than a random one
+  practice:bool,  // keeps a history that can be rewound through
+  rewinding:bool, // while the rewind key is held
+  rewound:bool,   // this game has been rewound, so isn't scored
+  history:History,
 }
 
-impl GameState {
-  const fn init() -> Self {
-    GameState {
-      board: Board::new(),
-      high_score: [0;2],
-      use_alt_high_score: 0,
+impl Game {
+  fn init(page:PageElements, storage:Storage, assets:String) -> Self {
+    let themes = Theme::built_ins(&assets);
+    Game {
+      page,
+      storage,
+      assets,
+      board: new_board(GameRules::init(),rand::seed()),
+      rules: GameRules::init(),
+      high_scores: Vec::new(),
       is_game_over: false,
       is_game_paused: true,
-      should_build_walls: true,
       should_mute_sfx: false,
+      should_mute_music: false,
+      movement_ticks: false,
+      themes,
+      theme: 0,
+      use_shapes: false,
+      viewport: Viewport::init(),
+      drawn_with_grace: false,
+      drawn_with_ghost: false,
+      effects: Effects::init(),
+      last_frame: 0.0,
+      menu: Menu::init(),
+      death: None,
+      stats: Stats::init(),
+      achievements: Achievements::init(),
+      daily: Daily::init(),
+      daily_day: None,
+      daily_counted: false,
+      best_runs: BestRuns::init(),
+      run: Run::init(),
+      ghost: None,
+      next_seed: None,
+      practice: false,
+      rewinding: false,
+      rewound: false,
+      history: History::init(),
     }
   }
   fn reset_game(&mut self) {
-    unsafe{PAGE_ELEMS.assume_init_ref().change_background(BkColour::Dark)};
-    let mut board = Board::new();
-    if self.should_build_walls {
-      board.generate_walls();
-      self.use_alt_high_score = 1;
-    } else {self.use_alt_high_score = 0;}
-    board.generate_snake().expect_throw("Failed to generate Snake");
-    draw_board(&board,unsafe{&(*PAGE_ELEMS.assume_init_ref()).context});
-    *self = GameState{
-      board,
-      is_game_over:false,
-      is_game_paused: true,
-      ..*self
+    self.storage.save("achievements",&self.achievements.to_json());
+    self.record_daily(self.death);
+    // Game over has counted it already, a game abandoned part way is counted
+    // here.
+    if !self.is_game_over && !self.rewound && self.board.query_ticks() > 0 {
+      self.record_stats(None);
+    }
+    self.page.change_background(BkColour::Dark,self.theme());
+    // Taken whichever board is next, so a seed left from a race started in
+    // the daily challenge isn't carried into a later game.
+    let next_seed = self.next_seed.take();
+    self.board = match self.daily_day {
+      Some(day) => new_board(daily::rules(day),daily::seed(day)),
+      None      => new_board(self.rules,next_seed.unwrap_or_else(rand::seed))
     };
-    update_score_display(
-      self.board.query_score(),
-      self.high_score[self.use_alt_high_score]
+    self.daily_counted = false;
+    self.run = Run::new(&self.board);
+    self.ghost = None;
+    self.history.clear();
+    self.rewound = false;
+    self.effects.clear();
+    self.shake_canvas((0.0,0.0));
+    self.menu.close();
+    self.death = None;
+    self.is_game_over = false;
+    self.is_game_paused = true;
+    self.redraw();
+    self.page.update_score(self.board.query_score(),self.high_score());
+    self.page.update_combo(self.board.query_combo());
+    self.page.update_hud(&self.board,None);
+  }
+  // Switches in or out of the daily challenge, on a fresh board.
+  fn play_daily(&mut self, day:Option<i64>) {
+    self.record_daily(self.death);
+    self.daily_day = day;
+    self.reset_game();
+  }
+  // Puts the board back a tick, out of game over if need be. Anything that
+  // relies on the game playing out as recorded stops following it.
+  fn rewind(&mut self) {
+    let Some(board) = self.history.pop() else {return};
+    self.board = board;
+    self.rewound = true;
+    self.ghost = None;
+    if self.is_game_over {
+      self.is_game_over = false;
+      self.death = None;
+      self.menu.close();
+      self.effects.clear();
+      self.shake_canvas((0.0,0.0));
+      self.page.change_background(BkColour::Dark,self.theme());
+    }
+    self.redraw();
+    self.page.update_score(self.board.query_score(),self.high_score());
+    self.page.update_combo(self.board.query_combo());
+    self.page.update_hud(&self.board,None);
+  }
+  fn practice_lines(&self) -> Vec<String> {
+    if !self.practice {return Vec::new()}
+    vec![if self.rewound {"Practice, rewound so not scored".into()}
+      else {"Practice, hold Backspace to rewind".into()}]
+  }
+  // Keeps the daily result once the run has started, whether it ended or was
+  // abandoned. Only the first of the day is kept.
+  fn record_daily(&mut self, death:Option<Death>) {
+    let Some(day) = self.daily_day else {return};
+    if self.board.query_ticks() == 0 {return}
+    if self.daily.record(DailyResult::new(day,&self.board,death)) {
+      self.daily_counted = true;
+      self.storage.save("dailyHistory",&self.daily.to_json());
+    }
+  }
+  fn record_stats(&mut self, death:Option<Death>) {
+    self.stats.record_game(&self.board,death);
+    self.storage.save(
+      "stats",
+      &serde_json::to_string(&self.stats.lifetime).unwrap_throw()
     );
   }
-}
-
-impl PageElements {
-  fn init() -> Self {
-    let document = web_sys::window().unwrap_throw()
-    .document().unwrap_throw();
-    let canvas = document
-      .get_element_by_id("canvas").unwrap_throw()
-      .dyn_into::<web_sys::HtmlCanvasElement>().unwrap_throw();
-    let context =  canvas
-      .get_context("2d").unwrap_throw().unwrap_throw()
-      .dyn_into::<web_sys::CanvasRenderingContext2d>().unwrap_throw();
-    let score = document
-      .get_element_by_id("score").unwrap_throw()
-      .dyn_into::<web_sys::HtmlElement>().unwrap_throw();
-    let high_score = document
-      .get_element_by_id("highscore").unwrap_throw()
-      .dyn_into::<web_sys::HtmlElement>().unwrap_throw();
-    let body = document.body().unwrap_throw()
-    .dyn_into::<web_sys::HtmlBodyElement>().unwrap_throw();
-    let sound_effects = SoundEffectElements::init(document);
-    PageElements {canvas,context,score,high_score,body,sound_effects}
-  }
-  fn change_background(&self,colour:BkColour) {
-    match colour {
-      BkColour::Dark  =>{
-        self.body.set_background("./assets/backgrounds/dark.png")
+  fn daily_lines(&self) -> Vec<String> {
+    let Some(day) = self.daily_day else {return Vec::new()};
+    let scored = self.daily.result(day).map(|result| {
+      if self.daily_counted {"This run counts for today".to_string()}
+      else {format!("Practice, today's score was {}",result.score)}
+    });
+    vec![
+      format!("Daily Challenge {}",daily::date(day)),
+      scored.unwrap_or("Your first run today counts".into())
+    ]
+  }
+  fn theme(&self) -> &Theme {
+    &self.themes[self.theme]
+  }
+  fn load_themes(&mut self) {
+    self.themes = Theme::built_ins(&self.assets);
+    if let Some(json) = self.storage.load("customThemes") {
+      self.themes.extend(
+        serde_json::from_str::<Vec<Theme>>(&json).unwrap_or_default()
+      );
+    }
+    let selected = self.storage.load("theme").unwrap_or_default();
+    self.theme = self.themes.iter()
+      .position(|theme| theme.name == selected)
+      .unwrap_or(0);
+  }
+  // Adds a theme, replacing any custom theme of the same name, and selects it.
+  fn import_theme(&mut self, theme:Theme) -> Result<(),String> {
+    let built_ins = Theme::built_ins(&self.assets).len();
+    let index = match self.themes.iter().position(|t| t.name == theme.name) {
+      Some(i) if i < built_ins => {
+        return Err(format!("\"{}\" is a built-in theme",theme.name))
       },
-      BkColour::Light =>{
-        self.body.set_background("./assets/backgrounds/light.png")
-      }
+      Some(i) => {self.themes[i] = theme; i},
+      None => {self.themes.push(theme); self.themes.len()-1}
+    };
+    self.storage.save(
+      "customThemes",
+      &serde_json::to_string(&self.themes[built_ins..]).unwrap_throw()
+    );
+    self.select_theme(index);
+    Ok(())
+  }
+  fn select_theme(&mut self, index:usize) {
+    self.theme = index % self.themes.len();
+    self.storage.save("theme",&self.theme().name);
+    self.page.change_background(
+      if self.is_game_over {BkColour::Light} else {BkColour::Dark},
+      self.theme()
+    );
+    self.redraw();
+  }
+  // Repaints the cells that changed since the last frame, and those effects
+  // were drawn over, unless the grace palette has been swapped in or out or
+  // an effect or ghost covers the board, which needs everything redrawn.
+  fn draw(&mut self) {
+    if self.board.query_grace() != self.drawn_with_grace
+      || self.effects.needs_redraw()
+      || self.menu.screen().is_some()
+      || self.drawn_with_ghost
+      || self.ghost.as_ref().and_then(Ghost::board).is_some() {
+      return self.redraw()
     }
+    let context = &self.page.context;
+    let mut cells = self.board.take_dirty_cells();
+    cells.extend(self.effects.take_covered());
+    draw_cells(
+      &self.board,
+      context,
+      &self.viewport,
+      &self.themes[self.theme],
+      self.use_shapes,
+      &cells
+    );
+    let theme = &self.themes[self.theme];
+    self.effects.draw(&self.board,context,&self.viewport,theme);
   }
-}
-
-impl SoundEffectElements {
-  fn init(document:web_sys::Document) -> Self {
-    let sfx_block = document.get_element_by_id("sfxblock").unwrap_throw();
-    let (apple,grace,game_over) = (
-      document
-        .create_element("audio").unwrap_throw()
-        .dyn_into::<web_sys::HtmlMediaElement>().unwrap_throw(),
-      document
-        .create_element("audio").unwrap_throw()
-        .dyn_into::<web_sys::HtmlMediaElement>().unwrap_throw(),
-      document
-        .create_element("audio").unwrap_throw()
-        .dyn_into::<web_sys::HtmlMediaElement>().unwrap_throw()
+  fn redraw(&mut self) {
+    let context = &self.page.context;
+    self.board.take_dirty_cells();
+    self.effects.take_covered();
+    self.drawn_with_grace = self.board.query_grace();
+    let ghost = self.ghost.as_ref().and_then(Ghost::board);
+    self.drawn_with_ghost = ghost.is_some();
+    draw_board(
+      &self.board,
+      context,
+      &self.viewport,
+      &self.themes[self.theme],
+      self.use_shapes,
+      ghost
     );
-    let mut ids = ["apple", "grace", "gameover"].into_iter();
-    let mut srcs = [
-      "./assets/sounds/apple.wav",
-      "./assets/sounds/grace.wav",
-      "./assets/sounds/gameover.wav",
-    ].into_iter();
-    for sfx in [&apple, &grace, &game_over].into_iter() {
-      sfx.set_id(ids.next().unwrap_throw());
-      sfx.set_src(srcs.next().unwrap_throw());
-      sfx.set_preload("auto");
-      sfx.set_volume(SFX_VOL);
-      sfx.load();
-      sfx_block.append_child(sfx).unwrap_throw();
+    let theme = &self.themes[self.theme];
+    self.effects.draw(&self.board,context,&self.viewport,theme);
+    if let Some(screen) = self.menu.screen() {
+      let items:Vec<String> = screen.actions().iter()
+        .map(|&action| match action {
+          Action::Change(setting) => {
+            format!("{}: {}",setting.label(),self.setting_value(setting))
+          },
+          _ => action.label().to_string()
+        })
+        .collect();
+      draw_menu(
+        context,
+        &self.viewport,
+        theme,
+        screen.title(),
+        &self.menu_lines(screen),
+        &items,
+        self.menu.selected()
+      );
+    }
+  }
+  fn menu_lines(&self, screen:Screen) -> Vec<Line> {
+    let text = match screen {
+      Screen::Title => vec![
+        format!("High Score: {}",self.high_score()),
+        match self.daily.result(daily::today()) {
+          Some(result) => format!("Today's Daily: {}",result.score),
+          None         => "Today's Daily: not played".into()
+        },
+        "Arrow keys steer, P pauses".into()
+      ],
+      Screen::Settings => vec![
+        "Game rules apply from the next game".into(),
+        format!(
+          "Tick Rate {}/s, Starting Length {}, Growth {}, \
+            {} per Apple, Full Board +{}",
+          self.rules.tick_rate,self.rules.starting_length,
+          self.rules.growth_per_apple,self.rules.score_per_apple,
+          self.rules.full_board_bonus
+        )
+      ],
+      Screen::Achievements => {
+        return Achievement::ALL.iter().map(|&achievement| {
+          let (progress,goal) = (
+            self.achievements.progress(achievement).min(achievement.goal()),
+            achievement.goal()
+          );
+          Line::Progress(
+            format!("{} - {} ({progress}/{goal})",
+              achievement.name(),achievement.description()),
+            progress as f64 / goal as f64
+          )
+        }).collect()
+      },
+      Screen::Paused => [
+        self.daily_lines(),
+        self.practice_lines(),
+        vec![format!("Score: {}",self.board.query_score())]
+      ].concat(),
+      Screen::GameOver => [self.daily_lines(),self.practice_lines(),vec![
+        self.death.map(describe_death).unwrap_or_default(),
+        format!("Score: {}",self.board.query_score()),
+        format!("High Score: {}",self.high_score()),
+        format!("Length: {}",self.board.query_body().count()),
+        format!("Grace Saves: {}",self.board.query_grace_saves()),
+        match &self.ghost {
+          Some(ghost) => format!("Ghost Score: {}",ghost.score()),
+          None        => String::new()
+        }
+      ]].concat()
+    };
+    text.into_iter().map(Line::Text).collect()
+  }
+  fn setting_value(&self, setting:Setting) -> String {
+    let on_off = |on:bool| if on {"On".to_string()} else {"Off".to_string()};
+    let percent = |channel| {
+      format!("{:.0}%",self.page.audio.volume(channel) * 100.0)
+    };
+    match setting {
+      Setting::Walls          => on_off(self.rules.build_walls),
+      Setting::Level          => {
+        format!("{} of {}",self.rules.level + 1,LEVELS.len())
+      },
+      Setting::Apples         => self.rules.apple_target.to_string(),
+      Setting::Feast          => on_off(self.rules.feast),
+      Setting::ExpiringApples => on_off(self.rules.expiring_apples),
+      Setting::GraceTicks     => self.rules.grace_ticks.to_string(),
+      Setting::Edges          => format!("{:?}",self.rules.edge_mode),
+      Setting::SoundEffects   => on_off(!self.should_mute_sfx),
+      Setting::Music          => on_off(!self.should_mute_music),
+      Setting::MasterVolume   => percent(Channel::Master),
+      Setting::SfxVolume      => percent(Channel::Sfx),
+      Setting::MusicVolume    => percent(Channel::Music),
+      Setting::MovementTicks  => on_off(self.movement_ticks),
+      Setting::Theme          => self.theme().name.clone(),
+      Setting::Shapes         => on_off(self.use_shapes),
+      Setting::ReducedMotion  => on_off(self.effects.reduced_motion)
+    }
+  }
+  // Steps a setting forwards (positive) or backwards through its values.
+  // Toggles flip whichever way they are stepped.
+  fn change_setting(&mut self, setting:Setting, step:isize) {
+    let cycle = |value:usize, count:usize| {
+      (value as isize + step).rem_euclid(count as isize) as usize
+    };
+    let rules = &mut self.rules;
+    match setting {
+      Setting::Walls          => rules.build_walls = !rules.build_walls,
+      Setting::Level          => rules.level = cycle(rules.level,LEVELS.len()),
+      Setting::Apples         => {
+        rules.apple_target = cycle(rules.apple_target - 1,MAX_APPLES) + 1
+      },
+      Setting::Feast          => rules.feast = !rules.feast,
+      Setting::ExpiringApples => rules.expiring_apples = !rules.expiring_apples,
+      Setting::GraceTicks     => {
+        rules.grace_ticks = cycle(rules.grace_ticks,MAX_GRACE_TICKS + 1)
+      },
+      Setting::Edges          => {
+        for _ in 0..cycle(0,3) {rules.edge_mode = rules.edge_mode.next()}
+      },
+      Setting::SoundEffects   => self.should_mute_sfx = !self.should_mute_sfx,
+      Setting::Music          => {
+        self.should_mute_music = !self.should_mute_music;
+        self.storage.save("muteMusic",&self.should_mute_music.to_string());
+        self.update_music();
+      },
+      Setting::MasterVolume   => self.step_volume(Channel::Master,step),
+      Setting::SfxVolume      => self.step_volume(Channel::Sfx,step),
+      Setting::MusicVolume    => self.step_volume(Channel::Music,step),
+      Setting::MovementTicks  => {
+        self.set_movement_ticks(!self.movement_ticks)
+      },
+      Setting::Theme          => {
+        self.select_theme(cycle(self.theme,self.themes.len()))
+      },
+      Setting::Shapes         => {
+        self.use_shapes = !self.use_shapes;
+        self.storage.save("shapes",&self.use_shapes.to_string());
+        self.redraw();
+      },
+      Setting::ReducedMotion  => {
+        let reduced_motion = !self.effects.reduced_motion;
+        self.effects.reduced_motion = reduced_motion;
+        self.storage.save("reducedMotion",&reduced_motion.to_string());
+        self.effects.clear();
+        self.shake_canvas((0.0,0.0));
+        self.redraw();
+      }
+    }
+  }
+  // In steps of 5%, the same as the volume sliders on the page.
+  fn step_volume(&self, channel:Channel, step:isize) {
+    let steps = (self.page.audio.volume(channel) * 20.0).round();
+    self.page.audio.set_volume(channel,(steps + step as f32) / 20.0);
+  }
+  fn start_game(&mut self) {
+    self.menu.close();
+    self.is_game_paused = false;
+    self.board.fill_apples().expect_throw("Failed to generate Apple");
+    if self.board.query_ticks() == 0 {
+      self.ghost = self.best_runs.best(&self.board)
+        .map(|run| Ghost::new(self.board.clone(),run));
+    }
+  }
+  fn turn(&mut self, direction:Direction) {
+    self.board.change_facing_direction(direction);
+    self.run.turns.push((self.board.query_ticks(),direction));
+  }
+  fn choose(&mut self, action:Action) {
+    match action {
+      Action::Play         => self.start_game(),
+      Action::Daily        => {
+        self.practice = false;
+        self.play_daily(Some(daily::today()));
+        self.start_game();
+      },
+      Action::Practice     => {
+        self.practice = true;
+        self.play_daily(None);
+        self.start_game();
+      },
+      Action::Resume       => {
+        self.menu.close();
+        self.is_game_paused = false;
+      },
+      Action::Retry        => {self.reset_game(); self.start_game()},
+      Action::RaceGhost    => {
+        self.next_seed = Some(self.board.query_seed());
+        self.reset_game();
+        self.start_game();
+      },
+      Action::OpenSettings => self.menu.open(Screen::Settings),
+      Action::OpenAchievements => self.menu.open(Screen::Achievements),
+      Action::ToTitle      => {
+        self.practice = false;
+        self.play_daily(None);
+        self.menu.open(Screen::Title);
+      },
+      Action::Back         => self.menu.back(),
+      Action::Change(s)    => self.change_setting(s,1)
+    }
+  }
+  // Handles the keys that navigate the open menu, returns false for any other
+  // key so it can still be used as a shortcut.
+  fn menu_keypress(&mut self, key:u8) -> bool {
+    let Some(screen) = self.menu.screen() else {return false};
+    let selected = self.menu.selected_action().unwrap_throw();
+    match key {
+      38 => self.menu.move_selection(-1),                 // Up Arrow
+      40 => self.menu.move_selection(1),                  // Down Arrow
+      13 | 32 => self.choose(selected),                   // Enter, Space
+      37 | 39 => if let Action::Change(setting) = selected { // Left, Right
+        self.change_setting(setting,if key == 37 {-1} else {1})
+      },
+      27 | 80 => match screen {                           // Escape, P
+        Screen::Settings | Screen::Achievements => self.menu.back(),
+        Screen::Paused   => self.choose(Action::Resume),
+        _                => {}
+      },
+      _  => return false
+    }
+    true
+  }
+  // Plays a sound from where the head of the snake is.
+  fn play(&self, sound:Sound) {
+    if self.should_mute_sfx {return}
+    let (head_x,_) = self.board.query_head_location().unwrap_throw();
+    self.page.audio.play(sound,head_x);
+  }
+  // Music follows the phase of the game, and speeds up with the tick rate.
+  fn update_music(&self) {
+    let (track,tempo) = if self.is_game_over {(Track::GameOver,1.0)}
+      else if self.is_game_paused {(Track::Menu,1.0)}
+      else {
+        let rate = self.board.query_rules().tick_rate;
+        (Track::Gameplay,rate / GameRules::init().tick_rate)
+      };
+    let track = if self.should_mute_music {None} else {Some(track)};
+    self.page.audio.play_music(track,tempo);
+  }
+  fn shake_canvas(&self, (x,y):(f64,f64)) {
+    let transform = if x == 0.0 && y == 0.0 {"none".into()}
+      else {format!("translate({x}px,{y}px)")};
+    self.page.canvas.style().set_property("transform",&transform)
+      .unwrap_throw();
+  }
+  // Sizes the canvas to fill its container (or the screen in fullscreen) with
+  // whole-pixel cells, and backs it with enough device pixels to stay crisp.
+  fn fit_canvas(&mut self) {
+    let pe = &self.page;
+    let window = web_sys::window().unwrap_throw();
+    let is_fullscreen = window.document().unwrap_throw()
+      .fullscreen_element().is_some();
+    let container = pe.canvas.parent_element().unwrap_throw();
+    let available_w = container.client_width() as f64;
+    let available_h = if is_fullscreen {container.client_height() as f64}
+      else {
+        window.inner_height().unwrap_throw().as_f64().unwrap_throw()
+          * MAX_HEIGHT_FRACTION
+      };
+    let cell = (available_w / GRID_W as f64)
+      .min(available_h / GRID_H as f64)
+      .floor()
+      .max(MIN_CELL_SIZE);
+    self.viewport = Viewport {
+      cell_w: cell,
+      cell_h: cell,
+      pixel_ratio: window.device_pixel_ratio()
+    };
+    let vp = self.viewport;
+    pe.canvas.set_width((vp.width() * vp.pixel_ratio).round() as u32);
+    pe.canvas.set_height((vp.height() * vp.pixel_ratio).round() as u32);
+    let style = pe.canvas.style();
+    style.set_property("width",&format!("{}px",vp.width())).unwrap_throw();
+    style.set_property("height",&format!("{}px",vp.height())).unwrap_throw();
+    // Resizing resets the context, so the scale is reapplied every time.
+    pe.context.set_transform(vp.pixel_ratio,0.0,0.0,vp.pixel_ratio,0.0,0.0)
+      .unwrap_throw();
+    self.redraw();
+  }
+  fn high_score(&self) -> u32 {
+    let bucket = self.board.query_rules().bucket();
+    self.high_scores.iter()
+      .find(|(b,_)| *b == bucket)
+      .map_or(0,|(_,high_score)| *high_score)
+  }
+  fn record_high_score(&mut self) {
+    if self.rewound {return}
+    let bucket = self.board.query_rules().bucket();
+    let score = self.board.query_score();
+    match self.high_scores.iter_mut().find(|(b,_)| *b == bucket) {
+      Some((_,high_score)) => *high_score = score.max(*high_score),
+      None => self.high_scores.push((bucket,score))
     }
-    SoundEffectElements{apple,grace,game_over}
   }
 }
 
-#[wasm_bindgen(js_name=queryTPS)]
-pub fn query_tps() -> f32 {
-  TPS
+fn describe_death(death:Death) -> String {
+  let at = |(x,y):(usize,usize)| format!("({x}, {y})");
+  let cause = match (death.cause,death.cell) {
+    (DeathCause::Edge,_)         => "Ran off the edge".to_string(),
+    (DeathCause::Wall,Some(c))   => format!("Hit a wall at {}",at(c)),
+    (DeathCause::Portal,Some(c)) => format!("Hit a portal at {}",at(c)),
+    (DeathCause::Tail,Some(c))   => format!("Bit its tail at {}",at(c)),
+    (DeathCause::Body,Some(c))   => format!(
+      "Bit segment {} at {}",death.body_index.unwrap_or_default(),at(c)
+    ),
+    (cause,None)                 => format!("{cause:?}")
+  };
+  format!("{cause}, head at {}",at(death.head))
 }
 
-fn update_score_display(score:u32, high_score:u32) {
-  let pe = unsafe{PAGE_ELEMS.assume_init_ref()};
-  pe.score.set_inner_html(format!("Score: {score}").as_str());
-  pe.high_score.set_inner_html(format!("High Score: {high_score}").as_str());
+// A board laid out for the start of a game, waiting for its first apples.
+fn new_board(rules:GameRules, seed:u64) -> Board {
+  let mut board = Board::seeded(rules,seed);
+  board.load_level(&LEVELS[rules.level]).expect_throw("Failed to load Level");
+  if rules.build_walls {board.generate_walls();}
+  board.generate_snake().expect_throw("Failed to generate Snake");
+  board
 }
 
+impl PageElements {
+  fn init(container:web_sys::HtmlElement, ids:&MountOptions)
+    -> Result<Self,String> {
+    let document = container.owner_document()
+      .ok_or("The container isn't in a document")?;
+    let inside = |id:&str| {
+      container.query_selector(&format!("#{}",web_sys::css::escape(id)))
+        .ok().flatten()
+    };
+    // The element with the id inside the container, or a new one added to it.
+    let find = |id:&str, tag:&str| -> Result<web_sys::Element,String> {
+      if let Some(element) = inside(id) {return Ok(element)}
+      let element = document.create_element(tag)
+        .map_err(|_| format!("Couldn't make a <{tag}>"))?;
+      element.set_id(id);
+      container.append_child(&element)
+        .map_err(|_| format!("Couldn't add #{id} to the container"))?;
+      Ok(element)
+    };
+    let text = |id:&str, tag:&str| -> Result<web_sys::HtmlElement,String> {
+      find(id,tag)?.dyn_into()
+        .map_err(|_| format!("#{id} isn't an HTML element"))
+    };
+    let score = text(&ids.score,"h2")?;
+    let combo = text(&ids.combo,"h2")?;
+    let high_score = text(&ids.high_score,"h2")?;
+    let hud = text(&ids.hud,"p")?;
+    let made_toast = inside(&ids.toast).is_none();
+    let toast = text(&ids.toast,"div")?;
+    if made_toast {toast.style().set_css_text(TOAST_STYLE)}
+    toast.style().set_property("opacity","0").unwrap_throw();
+    let canvas = find(&ids.canvas,"canvas")?
+      .dyn_into::<web_sys::HtmlCanvasElement>()
+      .map_err(|_| format!("#{} isn't a <canvas>",ids.canvas))?;
+    let context = canvas.get_context("2d").ok().flatten()
+      .and_then(|context| context.dyn_into().ok())
+      .ok_or("The canvas can't be drawn on in 2D")?;
+    let audio = AudioEngine::init(
+      Storage::new(&ids.namespace),
+      &ids.assets_dir()
+    );
+    Ok(PageElements {
+      canvas,
+      context,
+      score,
+      combo,
+      high_score,
+      hud,
+      toast,
+      toasts_shown: Rc::new(Cell::new(0)),
+      container,
+      audio
+    })
+  }
+  fn change_background(&self,colour:BkColour,theme:&Theme) {
+    let background = match colour {
+      BkColour::Dark  => &theme.background[0],
+      BkColour::Light => &theme.background[1]
+    };
+    self.container.style().set_property("background",background)
+      .unwrap_throw();
+  }
+  fn update_score(&self, score:u32, high_score:u32) {
+    self.score.set_inner_html(format!("Score: {score}").as_str());
+    self.high_score
+      .set_inner_html(format!("High Score: {high_score}").as_str());
+  }
+  fn update_combo(&self, combo:u32) {
+    self.combo.set_inner_html(format!("x{combo}").as_str());
+  }
+  // Pops up a notice over the page for a while, starting the wait again if
+  // one is already showing. Several at once just show the last.
+  fn show_toast(&self, achievement:Achievement) {
+    self.toast.set_inner_html(&format!(
+      "Achievement unlocked: <b>{}</b><br>{}",
+      achievement.name(),
+      achievement.description()
+    ));
+    self.toast.style().set_property("opacity","1").unwrap_throw();
+    let shown = self.toasts_shown.get() + 1;
+    self.toasts_shown.set(shown);
+    let (toast,toasts_shown) = (self.toast.clone(),self.toasts_shown.clone());
+    let hide = Closure::once_into_js(move || {
+      if toasts_shown.get() != shown {return}
+      let _ = toast.style().set_property("opacity","0");
+    });
+    web_sys::window().unwrap_throw()
+      .set_timeout_with_callback_and_timeout_and_arguments_0(
+        hide.unchecked_ref(),TOAST_TIME
+      ).unwrap_throw();
+  }
+  fn update_hud(&self, board:&Board, ghost:Option<&Ghost>) {
+    let rules = board.query_rules();
+    let seconds = board.query_elapsed() as u32;
+    // How far ahead of the ghost the score is, negative when behind.
+    let ghost = ghost.map(|ghost| {
+      let delta = board.query_score() as i64 - ghost.score() as i64;
+      format!(" | Ghost: {delta:+}")
+    });
+    self.hud.set_inner_html(format!(
+      "Length: {} | Apples: {} | Time: {}:{:02} | Ticks: {} | Speed: {} tps{}\
+        <br>{}",
+      board.query_length(),
+      board.query_apples_eaten(),
+      seconds / 60,
+      seconds % 60,
+      board.query_ticks(),
+      rules.tick_rate,
+      ghost.unwrap_or_default(),
+      rules.modifiers().join(" | ")
+    ).as_str());
+  }
+}
+
+#[wasm_bindgen]
+impl Game {
+  // Mounts a game into the container, the page body without one, using the
+  // elements with the ids in the options, the standard ids without them.
+  // Nothing is drawn until the game is started.
+  #[wasm_bindgen(constructor)]
+  pub fn mount(
+    container:Option<web_sys::HtmlElement>,
+    options:Option<MountOptions>
+  ) -> Result<Game,JsValue> {
+    let container = match container {
+      Some(container) => container,
+      None => web_sys::window().unwrap_throw()
+        .document().unwrap_throw()
+        .body().ok_or_else(|| JsValue::from_str("The page has no body"))?
+    };
+    let options = options.unwrap_or_else(MountOptions::standard);
+    let page = PageElements::init(container,&options)
+      .map_err(|e| JsValue::from_str(&e))?;
+    let mut game = Game::init(
+      page,
+      Storage::new(&options.namespace),
+      options.assets_dir()
+    );
+    game.load_themes();
+    Ok(game)
+  }
 
-#[wasm_bindgen(js_name = rustGameLoop)]
-pub fn rust_gameloop() {
-  let (gs,pe,sfx) = unsafe{(
-    &mut GAME_STATE,
-    PAGE_ELEMS.assume_init_ref(),
-    &(*PAGE_ELEMS.assume_init_ref()).sound_effects
-  )};
-  if gs.is_game_paused || gs.is_game_over {return;}
-  match gs.board.do_game_tick() {
-    GameEvent::GameOver => {
-      gs.is_game_over = true;
-      pe.change_background(BkColour::Light);
-      if !gs.should_mute_sfx {let _ = sfx.game_over.play().unwrap_throw();}
+  // Loads the saved settings and progress, and opens the title screen.
+  pub fn start(&mut self) {
+    self.page.canvas.set_attribute("tabindex","1").unwrap_throw();
+    if let Some(json) = self.storage.load("achievements") {
+      self.achievements = Achievements::from_json(&json);
     }
-    GameEvent::AppleCollected => {
-        if gs.board.query_score() > gs.high_score[gs.use_alt_high_score] {
-          gs.high_score[gs.use_alt_high_score] = gs.board.query_score();
-        }
-        update_score_display(
-          gs.board.query_score(),
-          gs.high_score[gs.use_alt_high_score]
-        );
-        if !gs.should_mute_sfx {let _ = sfx.apple.play().unwrap_throw();}
-      }
-    GameEvent::GraceTick => {
-      if !gs.should_mute_sfx {let _ = sfx.grace.play().unwrap_throw();}
+    if let Some(json) = self.storage.load("dailyHistory") {
+      self.daily = Daily::from_json(&json);
+    }
+    if let Some(json) = self.storage.load("ghostRuns") {
+      self.best_runs = BestRuns::from_json(&json);
     }
-    GameEvent::GeneralMovement => {}
+    if let Some(json) = self.storage.load("stats") {
+      self.stats.lifetime = serde_json::from_str(&json).unwrap_or_default();
+    }
+    self.use_shapes = self.storage.load("shapes").is_some_and(|v| v == "true");
+    self.should_mute_music =
+      self.storage.load("muteMusic").is_some_and(|v| v == "true");
+    self.movement_ticks =
+      self.storage.load("movementTicks").is_some_and(|v| v == "true");
+    // Follow the system setting until reduced motion is toggled in game.
+    self.effects.reduced_motion = match self.storage.load("reducedMotion") {
+      Some(setting) => setting == "true",
+      None => web_sys::window().unwrap_throw()
+        .match_media("(prefers-reduced-motion: reduce)").unwrap_throw()
+        .is_some_and(|query| query.matches())
+    };
+    self.reset_game();
+    self.menu.open(Screen::Title);
+    self.fit_canvas();
   }
-  draw_board(&gs.board,&pe.context);
-}
 
-#[wasm_bindgen(js_name = sendKeypress)]
-pub fn recieve_keypress(key:u8) {
-  let gs = unsafe {&mut GAME_STATE};
-  match key {
-    82 => gs.reset_game(),                                // R
-    87 => gs.should_build_walls = !gs.should_build_walls, // W
-    77 => gs.should_mute_sfx = !gs.should_mute_sfx,       // M
-    32 if gs.is_game_paused => {                          // Space
-      gs.is_game_paused = false;
-      gs.board.generate_apple().expect_throw("Failed to generate Apple");
-    }
-    _  => {}
+  // Silences the game and frees it. JS has to stop calling tick and animate
+  // first, the game can't be used again afterwards.
+  pub fn destroy(self) {
+    self.storage.save("achievements",&self.achievements.to_json());
+    self.page.audio.close();
+    self.shake_canvas((0.0,0.0));
   }
-  if !gs.is_game_over && !gs.is_game_paused {
-    match key {
-      38 => gs.board.change_facing_direction(Direction::Up), // Up Arrow
-      40 => gs.board.change_facing_direction(Direction::Dn), // Down Arrow
-      37 => gs.board.change_facing_direction(Direction::Lf), // Left Arrow
-      39 => gs.board.change_facing_direction(Direction::Rt), // Right Arrow
-      _  => {}
-    }
+
+  // The canvas the game is drawn on, and takes its keys from.
+  #[wasm_bindgen(getter)]
+  pub fn canvas(&self) -> web_sys::HtmlCanvasElement {
+    self.page.canvas.clone()
   }
-}
 
-fn draw_board(board:&Board, context:&web_sys::CanvasRenderingContext2d) {
-  fn inner_offset(cell:GridCell) -> (f64,f64) {
-    match cell {
-      GridCell::Snake(_,Direction::Dn) => (0.5  ,0.625),
-      GridCell::Snake(_,Direction::Up) => (0.5  ,0.375),
-      GridCell::Snake(_,Direction::Rt) => (0.625,0.5  ),
-      GridCell::Snake(_,Direction::Lf) => (0.375,0.5  ),
-      _                                => (0.5  ,0.5  )
-    }
+  #[wasm_bindgen(js_name=queryTPS)]
+  pub fn query_tps(&self) -> f32 {
+    self.board.query_rules().tick_rate
   }
-  fn colour(cell:GridCell,is_head:bool) -> [[JsValue;2];2] {
-    match cell {
-        GridCell::Nothing => [
-          [JsValue::from_str("#1d2021"),JsValue::from_str("#282828")],
-          [JsValue::from_str("#282828"),JsValue::from_str("#32302f")]
-        ],
-        GridCell::Snake(_, _) if is_head => [
-          [JsValue::from_str("#689d6a"),JsValue::from_str("#8ec07c")],
-          [JsValue::from_str("#427b58"),JsValue::from_str("#689d6a")]
-        ],
-        GridCell::Snake(_, _) => [
-          [JsValue::from_str("#98971a"),JsValue::from_str("#b6b926")],
-          [JsValue::from_str("#79740e"),JsValue::from_str("#98971a")]
-        ],
-        GridCell::Apple => [
-          [JsValue::from_str("#cc241d"),JsValue::from_str("#f74833")],
-          [JsValue::from_str("#9d0006"),JsValue::from_str("#cc241d")]
-        ],
-        GridCell::Wall => [
-          [JsValue::from_str("#3c3836"),JsValue::from_str("#50493c")],
-          [JsValue::from_str("#d5c6a1"),JsValue::from_str("#ebdbb2")]
-        ]
+
+  pub fn resize(&mut self) {
+    self.fit_canvas();
+  }
+
+  #[wasm_bindgen(js_name=toggleFullscreen)]
+  pub fn toggle_fullscreen(&self) {
+    let document = web_sys::window().unwrap_throw().document().unwrap_throw();
+    if document.fullscreen_element().is_some() {
+      document.exit_fullscreen();
+    } else {
+      let _ = self.page.canvas.parent_element().unwrap_throw()
+        .request_fullscreen();
     }
   }
-  let inner_scale:f64 = 0.375;
-  let inner_scale_sqrt = inner_scale.sqrt();
-  let grace_pallet:usize = if board.query_grace() {1} else {0};
-
-  // create a iterator of all drawn elements
-  let cells = (0..GRID_H).flat_map(|y|(0..GRID_W).map(move|x|{
-    (board.peek(x,y),(x,y))
-  }));
-  let snake_body = cells.clone()
-  .filter(|cell|match cell {(GridCell::Snake(..),..)=>true, _=>false});
-  let apples = cells.clone()
-  .filter(|cell|match cell {(GridCell::Apple,..)=>true, _=>false});
-  let walls = cells.clone()
-  .filter(|cell|match cell {(GridCell::Wall,..)=>true, _=>false});
-  let nothings = cells
-  .filter(|cell|match cell {(GridCell::Nothing,..)=>true, _=>false});
-  let drawn_elements = snake_body.chain(apples).chain(walls).chain(nothings);
-
-  // Draw Background (Nothing base colour)
-  context.set_fill_style(&colour(GridCell::Nothing,false)[grace_pallet][0]);
-  context.fill_rect(0f64,0f64,CANV_W as f64,CANV_H as f64);
-
-  { // Draw all cells
-    let mut prev_type = GridCell::Nothing; //Garenteed to overwrite instantly
-    for elem in drawn_elements.clone() {
-      let (gen_curr_type,(x,y)) = match elem {
-        (GridCell::Snake(..),(x,y))=> (GridCell::Snake(0,Direction::Rt),(x,y)),
-        (c,(x,y))                   => (c,(x,y))
-      };
-      let curr_type = match elem {(c,_) => c};
-      if curr_type == GridCell::Nothing {break}; //Don't redraw background.
-      if gen_curr_type != prev_type {
-        context.set_fill_style(&colour(gen_curr_type,false)[grace_pallet][0]);
-        prev_type = curr_type;
+
+  // Plays a game with a randomly steered snake, timing the original renderer,
+  // a full redraw and a dirty-cell redraw of every frame. Returns the average
+  // cost of each. Reading a pixel back after each one waits for the canvas to
+  // be painted, so the times include the browser's raster work.
+  #[wasm_bindgen(js_name=benchmarkRender)]
+  pub fn benchmark_render(&mut self, frames:u32) -> String {
+    let context = &self.page.context;
+    let performance = web_sys::window().unwrap_throw()
+      .performance().unwrap_throw();
+    let painted = || {
+      let _ = context.get_image_data(0.0,0.0,1.0,1.0);
+      performance.now()
+    };
+    let directions = [Direction::Up,Direction::Rt,Direction::Dn,Direction::Lf];
+    let mut board = new_board(self.rules,rand::seed());
+    board.fill_apples().expect_throw("Failed to generate Apple");
+    let (mut original_ms,mut full_ms,mut dirty_ms) = (0.0,0.0,0.0);
+    for frame in 0..frames {
+      if frame % 5 == 0 {
+        board.change_facing_direction(directions[rand::usize(0,4)]);
       }
-      context.fill_rect(
-        (x as u32 * CELL_W) as f64,
-        (y as u32 * CELL_H) as f64,
-        CELL_W as f64,
-        CELL_H as f64
+      if let GameEvent::GameOver(_) = board.do_game_tick() {
+        board = new_board(self.rules,rand::seed());
+        board.fill_apples().expect_throw("Failed to generate Apple");
+      }
+      let start = painted();
+      draw_board_original(&board,context,&self.viewport,self.theme());
+      let original = painted();
+      draw_board(
+        &board,context,&self.viewport,self.theme(),self.use_shapes,None
+      );
+      let full = painted();
+      let cells = board.take_dirty_cells();
+      draw_cells(
+        &board,context,&self.viewport,self.theme(),self.use_shapes,&cells
       );
+      dirty_ms += painted() - full;
+      full_ms += full - original;
+      original_ms += original - start;
     }
-    prev_type = GridCell::Nothing;
-    for elem in drawn_elements {
-      let (gen_curr_type,(x,y)) = match elem {
-          (GridCell::Snake(..),(x,y))=>(GridCell::Snake(0,Direction::Rt),(x,y)),
-          (c,(x,y))                  =>(c,(x,y))
-      };
-      let curr_type = match elem {(c,_) => c};
-      if gen_curr_type != prev_type {
-        context.set_fill_style(&colour(gen_curr_type,false)[grace_pallet][1]);
-        prev_type = curr_type;
+    self.redraw();
+    let frames = frames.max(1) as f64;
+    format!(
+      "original: {:.4}ms/frame, full redraw: {:.4}ms/frame, \
+        dirty cells: {:.4}ms/frame",
+      original_ms / frames,
+      full_ms / frames,
+      dirty_ms / frames
+    )
+  }
+
+  #[wasm_bindgen(js_name=queryRules)]
+  pub fn query_rules(&self) -> GameRules {
+    self.rules
+  }
+
+  #[wasm_bindgen(js_name=importTheme)]
+  pub fn import_theme_json(&mut self, json:&str) -> Result<(),JsValue> {
+    let theme = Theme::from_json(json).map_err(|e| JsValue::from_str(&e))?;
+    self.import_theme(theme).map_err(|e| JsValue::from_str(&e))
+  }
+
+  #[wasm_bindgen(js_name=queryThemes)]
+  pub fn query_themes(&self) -> Vec<String> {
+    self.themes.iter().map(|theme| theme.name.clone()).collect()
+  }
+
+  #[wasm_bindgen(js_name=queryTheme)]
+  pub fn query_theme(&self) -> String {
+    self.theme().name.clone()
+  }
+
+  #[wasm_bindgen(js_name=setTheme)]
+  pub fn set_theme(&mut self, name:&str) -> Result<(),JsValue> {
+    let index = self.themes.iter().position(|theme| theme.name == name)
+      .ok_or_else(|| JsValue::from_str(&format!("No theme named \"{name}\"")))?;
+    self.select_theme(index);
+    Ok(())
+  }
+
+  // Replaces the rules and resets onto a paused board played with them, out
+  // of the daily challenge and practice.
+  #[wasm_bindgen(js_name=setRules)]
+  pub fn set_rules(&mut self, rules:GameRules) {
+    self.rules = rules.validated();
+    self.practice = false;
+    self.play_daily(None);
+    self.fit_canvas();
+  }
+
+  #[wasm_bindgen(js_name=queryVolume)]
+  pub fn query_volume(&self, channel:Channel) -> f32 {
+    self.page.audio.volume(channel)
+  }
+
+  #[wasm_bindgen(js_name=setVolume)]
+  pub fn set_volume(&self, channel:Channel, volume:f32) {
+    self.page.audio.set_volume(channel,volume);
+  }
+
+  #[wasm_bindgen(js_name=queryMovementTicks)]
+  pub fn query_movement_ticks(&self) -> bool {
+    self.movement_ticks
+  }
+
+  #[wasm_bindgen(js_name=setMovementTicks)]
+  pub fn set_movement_ticks(&mut self, enabled:bool) {
+    self.movement_ticks = enabled;
+    self.storage.save("movementTicks",&enabled.to_string());
+  }
+
+  // Lifetime and session stats as JSON, grace saves included.
+  #[wasm_bindgen(js_name=queryStats)]
+  pub fn query_stats(&self) -> String {
+    self.stats.to_json()
+  }
+
+  // Every daily challenge result kept, as JSON.
+  #[wasm_bindgen(js_name=queryDailyHistory)]
+  pub fn query_daily_history(&self) -> String {
+    self.daily.to_json()
+  }
+
+  // Text to share for today's daily challenge, if it has been played.
+  #[wasm_bindgen(js_name=queryDailySummary)]
+  pub fn query_daily_summary(&self) -> Option<String> {
+    self.daily.result(daily::today()).map(DailyResult::summary)
+  }
+
+  pub fn tick(&mut self) {
+    self.update_music();
+    // Rewinding works after game over, but not behind the pause menu.
+    if self.rewinding && (self.is_game_over || !self.is_game_paused) {
+      return self.rewind();
+    }
+    if self.is_game_paused || self.is_game_over {return;}
+    if self.practice {self.history.push(&self.board)}
+    let game_event = self.board.do_game_tick();
+    if let Some(ghost) = &mut self.ghost {ghost.tick()}
+    // A rewound game has already been counted, or could count ticks twice.
+    let unlocked = if self.rewound {Vec::new()}
+      else {self.achievements.update(&game_event,&self.board)};
+    if !unlocked.is_empty() {
+      self.storage.save("achievements",&self.achievements.to_json());
+      for achievement in unlocked {self.page.show_toast(achievement)}
+    }
+    match game_event {
+      GameEvent::GameOver(death) => {
+        self.is_game_over = true;
+        self.death = Some(death);
+        self.record_daily(Some(death));
+        if !self.rewound {
+          self.record_stats(Some(death));
+          self.run.score = self.board.query_score();
+          if self.best_runs.submit(self.run.clone()) {
+            self.storage.save("ghostRuns",&self.best_runs.to_json());
+          }
+          self.storage.save("achievements",&self.achievements.to_json());
+        }
+        self.menu.open(Screen::GameOver);
+        self.effects.game_over(self.board.query_body().collect());
+        self.page.change_background(BkColour::Light,self.theme());
+        self.play(Sound::GameOver);
+      }
+      GameEvent::AppleCollected => {
+          self.record_high_score();
+          self.page.update_score(self.board.query_score(),self.high_score());
+          self.effects.apple_collected(
+            self.board.query_head_location().unwrap_throw()
+          );
+          self.play(Sound::Apple);
+        }
+      GameEvent::GraceTick => {
+        self.effects.grace_tick();
+        self.play(Sound::Grace);
+      }
+      GameEvent::GeneralMovement => {
+        if self.movement_ticks {self.play(Sound::Movement)}
       }
-      context.fill_rect(
-        (x as u32 * CELL_W) as f64 +
-          inner_offset(curr_type).0 * CELL_W as f64 * (1f64 - inner_scale_sqrt),
-        (y as u32 * CELL_H) as f64 +
-          inner_offset(curr_type).1 * CELL_H as f64 * (1f64 - inner_scale_sqrt),
-        CELL_W as f64 * inner_scale_sqrt,
-        CELL_H as f64 * inner_scale_sqrt
-      );
     }
+    self.page.update_combo(self.board.query_combo());
+    self.page.update_hud(&self.board,self.ghost.as_ref());
+    self.draw();
   }
 
-  {// Draw over the head of the snake with the snake head colours.
-    let (x,y) = board.query_head_location()
-      .expect_throw("Failed to locate head");
-    context.set_fill_style(
-      &colour(GridCell::Snake(0,Direction::Rt),true)[grace_pallet][0]
-    );
-    context.fill_rect(
-      (x as u32 * CELL_W) as f64,
-      (y as u32 * CELL_H) as f64,
-      CELL_W as f64,
-      CELL_H as f64
-    );
-    context.set_fill_style(
-      &colour(GridCell::Snake(0,Direction::Rt),true)[grace_pallet][1]
-    );
-    context.fill_rect(
-      (x as u32 * CELL_W) as f64 +
-        inner_offset(board.peek(x,y)).0*CELL_W as f64*(1f64 - inner_scale_sqrt),
-      (y as u32 * CELL_H) as f64 +
-        inner_offset(board.peek(x,y)).1*CELL_H as f64*(1f64 - inner_scale_sqrt),
-      CELL_W as f64 * inner_scale_sqrt,
-      CELL_H as f64 * inner_scale_sqrt
-    );
+  // Steps the effects on to the time of this animation frame and draws them.
+  pub fn animate(&mut self, now:f64) {
+    let elapsed = (now - self.last_frame).clamp(0.0,MAX_FRAME_TIME);
+    self.last_frame = now;
+    if self.effects.is_idle() {return}
+    self.effects.step(elapsed);
+    self.draw();
+    self.shake_canvas(self.effects.shake_offset(&self.viewport));
   }
 
-}
+  #[wasm_bindgen(js_name = sendKeypress)]
+  pub fn recieve_keypress(&mut self, key:u8) {
+    self.page.audio.resume();
+    if self.menu_keypress(key) {return self.redraw()}
+    match key {
+      82 => self.reset_game(),                                // R
+      87 => self.change_setting(Setting::Walls,1),            // W
+      76 => self.change_setting(Setting::Level,1),            // L
+      65 => self.change_setting(Setting::Apples,1),           // A
+      70 => self.change_setting(Setting::Feast,1),            // F
+      69 => self.change_setting(Setting::ExpiringApples,1),   // E
+      71 => self.change_setting(Setting::GraceTicks,1),       // G
+      66 => self.change_setting(Setting::Edges,1),            // B
+      77 => self.change_setting(Setting::SoundEffects,1),     // M
+      78 => self.change_setting(Setting::Music,1),            // N
+      84 => self.change_setting(Setting::Theme,1),            // T
+      90 => self.toggle_fullscreen(),                         // Z
+      67 => self.change_setting(Setting::Shapes,1),           // C
+      75 => self.change_setting(Setting::ReducedMotion,1),    // K
+      80 | 27 if !self.is_game_paused && !self.is_game_over => { // P, Escape
+        self.is_game_paused = true;
+        self.menu.open(Screen::Paused);
+      }
+      32 if self.is_game_paused => self.start_game(),         // Space
+      8 if self.practice => self.rewinding = true,            // Backspace
+      _  => {}
+    }
+    if self.menu.screen().is_some() {self.redraw()}
+    if !self.is_game_over && !self.is_game_paused {
+      match key {
+        38 => self.turn(Direction::Up), // Up Arrow
+        40 => self.turn(Direction::Dn), // Down Arrow
+        37 => self.turn(Direction::Lf), // Left Arrow
+        39 => self.turn(Direction::Rt), // Right Arrow
+        _  => {}
+      }
+    }
+  }
 
-#[wasm_bindgen(start)]
-pub fn main() {
-  unsafe {PAGE_ELEMS.write(PageElements::init());}
-  let (pe,gs) = unsafe{(PAGE_ELEMS.assume_init_ref(),&mut GAME_STATE)};
-  pe.canvas.set_attribute("Width", CANV_W.to_string().as_str()).unwrap_throw();
-  pe.canvas.set_attribute("Height", CANV_H.to_string().as_str()).unwrap_throw();
-  pe.canvas.set_attribute("tabindex","1").unwrap_throw();
-  gs.reset_game();
-  draw_board(&gs.board, &pe.context);
-}
\ No newline at end of file
+  #[wasm_bindgen(js_name = sendKeyRelease)]
+  pub fn recieve_key_release(&mut self, key:u8) {
+    if key == 8 {self.rewinding = false} // Backspace
+  }
+}
@@ -0,0 +1,177 @@
+// Screens drawn over the board. The menu only keeps track of where it is, the
+// game decides what each choice does.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Screen {Title, Settings, Achievements, Paused, GameOver}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+  Play,
+  Daily,
+  Practice,
+  Resume,
+  Retry,
+  RaceGhost,
+  OpenSettings,
+  OpenAchievements,
+  ToTitle,
+  Back,
+  Change(Setting)
+}
+
+// Every option that can be changed from the settings screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Setting {
+  Walls,
+  Level,
+  Apples,
+  Feast,
+  ExpiringApples,
+  GraceTicks,
+  Edges,
+  SoundEffects,
+  Music,
+  MasterVolume,
+  SfxVolume,
+  MusicVolume,
+  MovementTicks,
+  Theme,
+  Shapes,
+  ReducedMotion
+}
+
+impl Setting {
+  pub fn label(self) -> &'static str {
+    match self {
+      Setting::Walls          => "Walls",
+      Setting::Level          => "Level",
+      Setting::Apples         => "Apples",
+      Setting::Feast          => "Feast",
+      Setting::ExpiringApples => "Expiring Apples",
+      Setting::GraceTicks     => "Grace Ticks",
+      Setting::Edges          => "Edges",
+      Setting::SoundEffects   => "Sound Effects",
+      Setting::Music          => "Music",
+      Setting::MasterVolume   => "Master Volume",
+      Setting::SfxVolume      => "Effects Volume",
+      Setting::MusicVolume    => "Music Volume",
+      Setting::MovementTicks  => "Movement Ticks",
+      Setting::Theme          => "Theme",
+      Setting::Shapes         => "Shapes",
+      Setting::ReducedMotion  => "Reduced Motion"
+    }
+  }
+}
+
+impl Screen {
+  pub fn title(self) -> &'static str {
+    match self {
+      Screen::Title        => "Snake",
+      Screen::Settings     => "Settings",
+      Screen::Achievements => "Achievements",
+      Screen::Paused       => "Paused",
+      Screen::GameOver     => "Game Over"
+    }
+  }
+  pub fn actions(self) -> &'static [Action] {
+    match self {
+      Screen::Title        => &[
+        Action::Play,
+        Action::Daily,
+        Action::Practice,
+        Action::OpenSettings,
+        Action::OpenAchievements
+      ],
+      Screen::Achievements => &[Action::Back],
+      Screen::Paused       => &[
+        Action::Resume,Action::Retry,Action::OpenSettings,Action::ToTitle
+      ],
+      Screen::GameOver     => &[
+        Action::Retry,Action::RaceGhost,Action::ToTitle
+      ],
+      Screen::Settings     => &[
+        Action::Change(Setting::Walls),
+        Action::Change(Setting::Level),
+        Action::Change(Setting::Apples),
+        Action::Change(Setting::Feast),
+        Action::Change(Setting::ExpiringApples),
+        Action::Change(Setting::GraceTicks),
+        Action::Change(Setting::Edges),
+        Action::Change(Setting::SoundEffects),
+        Action::Change(Setting::Music),
+        Action::Change(Setting::MasterVolume),
+        Action::Change(Setting::SfxVolume),
+        Action::Change(Setting::MusicVolume),
+        Action::Change(Setting::MovementTicks),
+        Action::Change(Setting::Theme),
+        Action::Change(Setting::Shapes),
+        Action::Change(Setting::ReducedMotion),
+        Action::Back
+      ]
+    }
+  }
+}
+
+impl Action {
+  pub fn label(self) -> &'static str {
+    match self {
+      Action::Play             => "Play",
+      Action::Daily            => "Daily Challenge",
+      Action::Practice         => "Practice",
+      Action::Resume           => "Resume",
+      Action::Retry            => "Retry",
+      Action::RaceGhost        => "Race Ghost",
+      Action::OpenSettings     => "Settings",
+      Action::OpenAchievements => "Achievements",
+      Action::ToTitle          => "Main Menu",
+      Action::Back             => "Back",
+      Action::Change(s)        => s.label()
+    }
+  }
+}
+
+pub struct Menu {
+  screen:Option<Screen>,   // None while playing
+  previous:Option<Screen>, // where going back returns to
+  selected:usize,
+}
+
+impl Menu {
+  pub const fn init() -> Self {
+    Menu {screen: None, previous: None, selected: 0}
+  }
+
+  pub fn screen(&self) -> Option<Screen> {
+    self.screen
+  }
+
+  pub fn selected(&self) -> usize {
+    self.selected
+  }
+
+  pub fn selected_action(&self) -> Option<Action> {
+    Some(self.screen?.actions()[self.selected])
+  }
+
+  pub fn open(&mut self, screen:Screen) {
+    self.previous = self.screen;
+    self.screen = Some(screen);
+    self.selected = 0;
+  }
+
+  pub fn close(&mut self) {
+    *self = Menu::init();
+  }
+
+  pub fn back(&mut self) {
+    self.screen = self.previous.take();
+    self.selected = 0;
+  }
+
+  // Moves the selection up (negative) or down, wrapping round the ends.
+  pub fn move_selection(&mut self, step:isize) {
+    let Some(screen) = self.screen else {return};
+    let count = screen.actions().len() as isize;
+    self.selected = (self.selected as isize + step).rem_euclid(count) as usize;
+  }
+}
@@ -0,0 +1,169 @@
+use std::cell::RefCell;
+use wasm_bindgen::prelude::*;
+use web_sys::{
+  AudioBuffer, AudioBufferSourceNode, AudioContext, AudioScheduledSourceNode,
+  GainNode
+};
+
+const CROSSFADE:f64 = 1.0;     // in seconds
+const MIN_TEMPO:f32 = 0.5;     // playback rate limits
+const MAX_TEMPO:f32 = 2.0;
+const REST:i8 = i8::MIN;
+const ATTACK:f32 = 0.005;      // in seconds
+const RELEASE:f32 = 0.01;
+const DECAY:f32 = 6.0;         // per second
+const LEAD_VOLUME:f32 = 0.12;
+const BASS_VOLUME:f32 = 0.2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Track {Menu, Gameplay, GameOver}
+
+// A loop of notes, in semitones from A4, one per step. The lead is played
+// on a square wave and the bass on a triangle wave.
+struct Score {
+  step:f32, // in seconds
+  lead:&'static [i8],
+  bass:&'static [i8],
+}
+
+const MENU:Score = Score {
+  step: 0.3,
+  lead: &[
+    0,3,7,3, -2,2,5,2, -4,0,3,0, -5,-1,2,-1,
+  ],
+  bass: &[
+    -24,REST,REST,REST, -26,REST,REST,REST,
+    -28,REST,REST,REST, -29,REST,REST,REST,
+  ],
+};
+
+// Written for the standard tick rate, played faster or slower with the game.
+const GAMEPLAY:Score = Score {
+  step: 0.15,
+  lead: &[
+    7,REST,7,10, 12,REST,10,7, 5,REST,5,7, 3,REST,REST,REST,
+    7,REST,7,10, 12,REST,15,12, 10,REST,7,5, 7,REST,REST,REST,
+  ],
+  bass: &[
+    -24,-12,-24,-12, -24,-12,-24,-12, -28,-16,-28,-16, -28,-16,-28,-16,
+    -26,-14,-26,-14, -26,-14,-26,-14, -29,-17,-29,-17, -29,-17,-29,-17,
+  ],
+};
+
+const GAME_OVER:Score = Score {
+  step: 0.45,
+  lead: &[
+    0,REST,-2,REST, -4,REST,-5,REST, -7,REST,-9,REST, -12,REST,REST,REST,
+  ],
+  bass: &[
+    -24,REST,REST,REST, -28,REST,REST,REST,
+    -31,REST,REST,REST, -36,REST,REST,REST,
+  ],
+};
+
+impl Track {
+  fn score(self) -> &'static Score {
+    match self {
+      Track::Menu     => &MENU,
+      Track::Gameplay => &GAMEPLAY,
+      Track::GameOver => &GAME_OVER
+    }
+  }
+}
+
+// Loops one track at a time into the music channel, crossfading whenever the
+// track changes. There are no music files, every track is synthesised into a
+// buffer up front.
+pub struct Music {
+  context:AudioContext,
+  output:GainNode,
+  tracks:[AudioBuffer;3],
+  playing:RefCell<Option<(Track,AudioBufferSourceNode,GainNode)>>,
+}
+
+impl Music {
+  pub fn init(context:&AudioContext, output:&GainNode) -> Self {
+    let tracks = [Track::Menu,Track::Gameplay,Track::GameOver]
+      .map(|track| synthesise(context,track.score()));
+    Music {
+      context: context.clone(),
+      output: output.clone(),
+      tracks,
+      playing: RefCell::new(None)
+    }
+  }
+
+  // Switches to the given track (or silence) at the given tempo, where 1.0 is
+  // the speed it was written at. Asking for what is already playing only
+  // changes the tempo.
+  pub fn play(&self, track:Option<Track>, tempo:f32) {
+    let tempo = tempo.clamp(MIN_TEMPO,MAX_TEMPO);
+    let mut playing = self.playing.borrow_mut();
+    if let Some((current,source,_)) = playing.as_ref() {
+      if Some(*current) == track {
+        source.playback_rate().set_value(tempo);
+        return;
+      }
+    }
+    let now = self.context.current_time();
+    if let Some((_,source,gain)) = playing.take() {
+      gain.gain().set_value_at_time(gain.gain().value(),now).unwrap_throw();
+      gain.gain().linear_ramp_to_value_at_time(0.0,now + CROSSFADE)
+        .unwrap_throw();
+      AudioScheduledSourceNode::stop_with_when(&source,now + CROSSFADE)
+        .unwrap_throw();
+    }
+    let Some(track) = track else {return};
+    let gain = self.context.create_gain().unwrap_throw();
+    gain.gain().set_value_at_time(0.0,now).unwrap_throw();
+    gain.gain().linear_ramp_to_value_at_time(1.0,now + CROSSFADE)
+      .unwrap_throw();
+    let source = self.context.create_buffer_source().unwrap_throw();
+    source.set_buffer(Some(&self.tracks[track as usize]));
+    source.set_loop(true);
+    source.playback_rate().set_value(tempo);
+    source.connect_with_audio_node(&gain).unwrap_throw()
+      .connect_with_audio_node(&self.output).unwrap_throw();
+    source.start().unwrap_throw();
+    *playing = Some((track,source,gain));
+  }
+}
+
+// Waves over one period, phase runs from 0 to 1.
+fn square(phase:f32) -> f32 {
+  if phase < 0.5 {1.0} else {-1.0}
+}
+
+fn triangle(phase:f32) -> f32 {
+  4.0 * (phase - 0.5).abs() - 1.0
+}
+
+fn synthesise(context:&AudioContext, score:&Score) -> AudioBuffer {
+  let sample_rate = context.sample_rate();
+  let step_len = (score.step * sample_rate) as usize;
+  let mut samples = vec![0f32;step_len * score.lead.len()];
+  let voices = [
+    (score.lead,square as fn(f32) -> f32,LEAD_VOLUME),
+    (score.bass,triangle,BASS_VOLUME)
+  ];
+  for (notes,wave,volume) in voices {
+    for (step,&note) in notes.iter().enumerate() {
+      if note == REST {continue}
+      let frequency = 440.0 * 2f32.powf(note as f32 / 12.0);
+      let start = step * step_len;
+      let note_samples = &mut samples[start..start + step_len];
+      for (i,sample) in note_samples.iter_mut().enumerate() {
+        let t = i as f32 / sample_rate;
+        // Short fades at both ends of every note keep them from clicking.
+        let envelope = (t / ATTACK).min(1.0)
+          * (-t * DECAY).exp()
+          * ((score.step - t) / RELEASE).min(1.0);
+        *sample += wave((t * frequency).fract()) * volume * envelope;
+      }
+    }
+  }
+  let buffer = context
+    .create_buffer(1,samples.len() as u32,sample_rate).unwrap_throw();
+  buffer.copy_to_channel(&samples,0).unwrap_throw();
+  buffer
+}
@@ -6,9 +6,57 @@ pub fn usize(min:usize, max:usize) -> usize {
   (random()*(max as f64 - min as f64) + min as f64).floor() as usize
 }
 
+pub fn f64(min:f64, max:f64) -> f64 {
+  random()*(max - min) + min
+}
+
+// A fresh seed for a game nobody needs to play again.
+pub fn seed() -> u64 {
+  (random() * (1u64 << 53) as f64) as u64
+}
+
+// SplitMix64. Anything that has to play out the same way from the same seed
+// draws from one of these rather than from Math.random.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rng {
+  state:u64,
+}
+
+const GAMMA:u64 = 0x9e3779b97f4a7c15;
+
+impl Rng {
+  pub const fn new(seed:u64) -> Self {
+    Rng {state: seed}
+  }
+
+  // The nth of any number of separate sequences drawn from the same seed,
+  // found without drawing from any of the others.
+  pub fn stream(seed:u64, n:u64) -> Self {
+    let mut rng = Rng::new(seed.wrapping_add(n.wrapping_mul(GAMMA)));
+    Rng::new(rng.next())
+  }
+
+  fn next(&mut self) -> u64 {
+    self.state = self.state.wrapping_add(GAMMA);
+    let mut z = self.state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+  }
+
+  // Top 53 bits, as an f64 in [0,1).
+  fn random(&mut self) -> f64 {
+    (self.next() >> 11) as f64 / (1u64 << 53) as f64
+  }
+
+  pub fn usize(&mut self, min:usize, max:usize) -> usize {
+    (self.random()*(max as f64 - min as f64) + min as f64).floor() as usize
+  }
+}
+
 #[wasm_bindgen]
 extern "C" {
   #[wasm_bindgen(js_namespace = Math, js_name = random)]
   fn random() -> f64;
 
-}
\ No newline at end of file
+}
@@ -0,0 +1,1048 @@
+use crate::game_logic::*;
+use crate::theme::Theme;
+use wasm_bindgen::UnwrapThrowExt;
+
+// The drawing operations the board is rendered with. Implemented by the
+// canvas context on the page, and by a recorder in tests so what gets drawn
+// can be checked without a browser.
+pub trait Renderer {
+  fn set_fill_style(&self, style:&str);
+  fn set_stroke_style(&self, style:&str);
+  fn set_line_width(&self, width:f64);
+  fn set_global_alpha(&self, alpha:f64);
+  fn set_font(&self, font:&str);
+  fn set_text_align(&self, align:&str);
+  fn set_text_baseline(&self, baseline:&str);
+  fn fill_rect(&self, x:f64, y:f64, w:f64, h:f64);
+  fn fill_text(&self, text:&str, x:f64, y:f64);
+  fn begin_path(&self);
+  fn move_to(&self, x:f64, y:f64);
+  fn line_to(&self, x:f64, y:f64);
+  fn arc(&self, x:f64, y:f64, radius:f64, start:f64, end:f64);
+  fn fill(&self);
+  fn stroke(&self);
+}
+
+impl Renderer for web_sys::CanvasRenderingContext2d {
+  fn set_fill_style(&self, style:&str) {self.set_fill_style_str(style)}
+  fn set_stroke_style(&self, style:&str) {self.set_stroke_style_str(style)}
+  fn set_line_width(&self, width:f64) {self.set_line_width(width)}
+  fn set_global_alpha(&self, alpha:f64) {self.set_global_alpha(alpha)}
+  fn set_font(&self, font:&str) {self.set_font(font)}
+  fn set_text_align(&self, align:&str) {self.set_text_align(align)}
+  fn set_text_baseline(&self, baseline:&str) {self.set_text_baseline(baseline)}
+  fn fill_rect(&self, x:f64, y:f64, w:f64, h:f64) {self.fill_rect(x,y,w,h)}
+  fn fill_text(&self, text:&str, x:f64, y:f64) {
+    self.fill_text(text,x,y).unwrap_throw()
+  }
+  fn begin_path(&self) {self.begin_path()}
+  fn move_to(&self, x:f64, y:f64) {self.move_to(x,y)}
+  fn line_to(&self, x:f64, y:f64) {self.line_to(x,y)}
+  fn arc(&self, x:f64, y:f64, radius:f64, start:f64, end:f64) {
+    self.arc(x,y,radius,start,end).unwrap_throw()
+  }
+  fn fill(&self) {self.fill()}
+  fn stroke(&self) {self.stroke()}
+}
+
+// Size of the board on screen. Cells are square so the canvas keeps the 3:2
+// aspect ratio of the grid, and are recomputed whenever the page resizes.
+#[derive(Debug, Clone, Copy)]
+pub struct Viewport {
+  pub cell_w:f64,       // in CSS pixels
+  pub cell_h:f64,
+  pub pixel_ratio:f64,  // device pixels per CSS pixel
+}
+
+impl Viewport {
+  pub const fn init() -> Self {
+    Viewport {cell_w: 40.0, cell_h: 40.0, pixel_ratio: 1.0}
+  }
+  pub fn width(&self) -> f64 {
+    self.cell_w * GRID_W as f64
+  }
+  pub fn height(&self) -> f64 {
+    self.cell_h * GRID_H as f64
+  }
+}
+
+const BODY_WIDTH:f64 = 0.7;    // fraction of a cell
+const TAIL_WIDTH:f64 = 0.3;
+const TAPER_SEGMENTS:usize = 4; // segments the tail narrows over
+const INNER_BAND:f64 = 0.45;    // inner colour band, fraction of the body
+const GHOST_ALPHA:f64 = 0.35;
+
+// The snake is drawn over empty cells as a band, see draw_segment.
+fn background(cell:GridCell) -> GridCell {
+  match cell {
+    GridCell::Snake(..) => GridCell::Nothing,
+    c                   => c
+  }
+}
+
+// Cells drawn with a shape instead of an inner square in shapes mode.
+fn has_shape(cell:GridCell) -> bool {
+  matches!(cell,GridCell::Apple(_) | GridCell::Wall | GridCell::Portal(_))
+}
+
+// Position and size (x,y,w,h) of the inner square of the cell at (x,y).
+fn inner_rect(board:&Board, vp:&Viewport, x:usize, y:usize)
+  -> (f64,f64,f64,f64) {
+  let inner_scale:f64 = 0.375;
+  let cell = background(board.peek(x,y));
+  // Expiring apples shrink their inner square as a countdown timer.
+  let scale = match cell {
+    GridCell::Apple(t) => inner_scale.sqrt() * board.query_apple_freshness(t),
+    _                  => inner_scale.sqrt()
+  };
+  (
+    x as f64 * vp.cell_w + 0.5 * vp.cell_w * (1f64 - scale),
+    y as f64 * vp.cell_h + 0.5 * vp.cell_h * (1f64 - scale),
+    vp.cell_w * scale,
+    vp.cell_h * scale
+  )
+}
+
+// Draws everything, with the snake of a ghost board if given faded out
+// underneath the live snake.
+pub fn draw_board(
+  board:&Board,
+  context:&impl Renderer,
+  vp:&Viewport,
+  theme:&Theme,
+  use_shapes:bool,
+  ghost:Option<&Board>
+) {
+  let colour = |cell,is_head| theme.colour(cell,is_head,board.query_grace());
+  let (cell_w,cell_h) = (vp.cell_w,vp.cell_h);
+
+  // create a iterator of all drawn elements
+  let cells = (0..GRID_H).flat_map(|y|(0..GRID_W).map(move|x|{
+    (board.peek(x,y),(x,y))
+  }));
+  let apples = cells.clone()
+  .filter(|cell|matches!(cell,(GridCell::Apple(_),..)));
+  let walls = cells.clone()
+  .filter(|cell|matches!(cell,(GridCell::Wall,..)));
+  let portals = cells.clone()
+  .filter(|cell|matches!(cell,(GridCell::Portal(..),..)));
+  let nothings = cells
+  .filter(|cell|matches!(cell,(GridCell::Nothing|GridCell::Snake(..),..)));
+  let drawn_elements = apples.chain(walls).chain(portals).chain(nothings);
+
+  // Draw Background (Nothing base colour)
+  context.set_fill_style(&colour(GridCell::Nothing,false)[0]);
+  context.fill_rect(0f64,0f64,vp.width(),vp.height());
+
+  { // Draw all cells
+    let mut prev_type = GridCell::Nothing; //Garenteed to overwrite instantly
+    for elem in drawn_elements.clone() {
+      let (gen_curr_type,(x,y)) = match elem {
+        (GridCell::Snake(..),(x,y))=> (GridCell::Nothing,(x,y)),
+        (GridCell::Portal(_),(x,y))=> (GridCell::Portal(0),(x,y)),
+        (GridCell::Apple(_),(x,y)) => (GridCell::Apple(0),(x,y)),
+        (c,(x,y))                   => (c,(x,y))
+      };
+      if gen_curr_type == GridCell::Nothing {break}; //Don't redraw background.
+      if gen_curr_type != prev_type {
+        context.set_fill_style(&colour(gen_curr_type,false)[0]);
+        prev_type = gen_curr_type;
+      }
+      context.fill_rect(
+        x as f64 * cell_w,
+        y as f64 * cell_h,
+        cell_w,
+        cell_h
+      );
+    }
+    prev_type = GridCell::Nothing;
+    for elem in drawn_elements {
+      let (gen_curr_type,(x,y)) = match elem {
+          (GridCell::Snake(..),(x,y))=>(GridCell::Nothing,(x,y)),
+          (GridCell::Portal(_),(x,y))=>(GridCell::Portal(0),(x,y)),
+          (GridCell::Apple(_),(x,y)) =>(GridCell::Apple(0),(x,y)),
+          (c,(x,y))                  =>(c,(x,y))
+      };
+      let (curr_type,_) = elem;
+      if use_shapes && has_shape(curr_type) {continue};
+      if gen_curr_type != prev_type {
+        context.set_fill_style(&colour(gen_curr_type,false)[1]);
+        prev_type = gen_curr_type;
+      }
+      let (inner_x,inner_y,inner_w,inner_h) = inner_rect(board,vp,x,y);
+      context.fill_rect(inner_x,inner_y,inner_w,inner_h);
+    }
+  }
+
+  for (x,y) in (0..GRID_H).flat_map(|y| (0..GRID_W).map(move |x| (x,y))) {
+    if use_shapes {draw_shape(board,context,vp,theme,x,y);}
+    draw_portal_id(board,context,vp,theme,use_shapes,x,y);
+  }
+  if let Some(ghost) = ghost {
+    context.set_global_alpha(GHOST_ALPHA);
+    draw_snake(ghost,context,vp,theme);
+    context.set_global_alpha(1.0);
+  }
+  draw_snake(board,context,vp,theme);
+  draw_edges(board,context,vp,theme);
+}
+
+fn draw_snake(
+  board:&Board,
+  context:&impl Renderer,
+  vp:&Viewport,
+  theme:&Theme
+) {
+  let body:Vec<(usize,usize)> = board.query_body().collect();
+  for i in (1..body.len()).rev() {
+    draw_segment(board,context,vp,theme,&body,i);
+  }
+  draw_head(board,context,vp,theme);
+}
+
+// The board the way it was drawn before dirty cells and the Renderer trait:
+// every cell as an outer and inner square, all of it every frame. Only kept
+// as the "before" the render benchmark compares against.
+pub fn draw_board_original(
+  board:&Board,
+  context:&impl Renderer,
+  vp:&Viewport,
+  theme:&Theme
+) {
+  let colour = |cell,is_head| theme.colour(cell,is_head,board.query_grace());
+  let inner_offset = |cell| match cell {
+    GridCell::Snake(_,Direction::Dn) => (0.5  ,0.625),
+    GridCell::Snake(_,Direction::Up) => (0.5  ,0.375),
+    GridCell::Snake(_,Direction::Rt) => (0.625,0.5  ),
+    GridCell::Snake(_,Direction::Lf) => (0.375,0.5  ),
+    _                                => (0.5  ,0.5  )
+  };
+  // Snake, apples, walls and portals, then the empty cells.
+  let kind = |cell| match cell {
+    GridCell::Snake(..) => GridCell::Snake(0,Direction::Rt),
+    GridCell::Apple(_)  => GridCell::Apple(0),
+    GridCell::Portal(_) => GridCell::Portal(0),
+    c                   => c
+  };
+  let order = |cell| match cell {
+    GridCell::Snake(..) => 0,
+    GridCell::Apple(_)  => 1,
+    GridCell::Wall      => 2,
+    GridCell::Portal(_) => 3,
+    GridCell::Nothing   => 4
+  };
+  let mut cells:Vec<(GridCell,(usize,usize))> = (0..GRID_H)
+    .flat_map(|y| (0..GRID_W).map(move |x| (board.peek(x,y),(x,y))))
+    .collect();
+  cells.sort_by_key(|&(cell,_)| order(cell));
+  let scale = 0.375f64.sqrt();
+  let outer = |(x,y):(usize,usize)| {
+    context.fill_rect(x as f64 * vp.cell_w,y as f64 * vp.cell_h,
+      vp.cell_w,vp.cell_h);
+  };
+  let inner = |cell,(x,y):(usize,usize)| {
+    let (offset_x,offset_y) = inner_offset(cell);
+    context.fill_rect(
+      x as f64 * vp.cell_w + offset_x * vp.cell_w * (1.0 - scale),
+      y as f64 * vp.cell_h + offset_y * vp.cell_h * (1.0 - scale),
+      vp.cell_w * scale,
+      vp.cell_h * scale
+    );
+  };
+
+  context.set_fill_style(&colour(GridCell::Nothing,false)[0]);
+  context.fill_rect(0.0,0.0,vp.width(),vp.height());
+  let mut prev_type = GridCell::Nothing;
+  for &(cell,pos) in cells.iter().take_while(|(c,_)| *c != GridCell::Nothing) {
+    if kind(cell) != prev_type {
+      context.set_fill_style(&colour(kind(cell),false)[0]);
+      prev_type = cell;
+    }
+    outer(pos);
+  }
+  prev_type = GridCell::Nothing;
+  for &(cell,pos) in &cells {
+    if kind(cell) != prev_type {
+      context.set_fill_style(&colour(kind(cell),false)[1]);
+      prev_type = cell;
+    }
+    inner(cell,pos);
+  }
+  if let Some(head) = board.query_head_location() {
+    let colours = colour(GridCell::Snake(0,Direction::Rt),true);
+    context.set_fill_style(&colours[0]);
+    outer(head);
+    context.set_fill_style(&colours[1]);
+    inner(board.peek(head.0,head.1),head);
+  }
+}
+
+// Repaints just the given cells over the last frame, for when only a handful
+// of cells have changed since it was drawn.
+pub fn draw_cells(
+  board:&Board,
+  context:&impl Renderer,
+  vp:&Viewport,
+  theme:&Theme,
+  use_shapes:bool,
+  cells:&[(usize,usize)]
+) {
+  let colour = |cell| theme.colour(cell,false,board.query_grace());
+  // The tapered end of the tail changes width every time the snake moves.
+  let body:Vec<(usize,usize)> = board.query_body().collect();
+  let tail = &body[body.len().saturating_sub(TAPER_SEGMENTS+1)..];
+  let cells:Vec<(usize,usize)> = cells.iter()
+    .chain(tail.iter().filter(|cell| !cells.contains(cell)))
+    .copied()
+    .collect();
+  for &(x,y) in &cells {
+    let cell = background(board.peek(x,y));
+    context.set_fill_style(&colour(cell)[0]);
+    context.fill_rect(x as f64 * vp.cell_w,y as f64 * vp.cell_h,
+      vp.cell_w,vp.cell_h);
+    if use_shapes && has_shape(cell) {
+      draw_shape(board,context,vp,theme,x,y);
+    } else {
+      let (inner_x,inner_y,inner_w,inner_h) = inner_rect(board,vp,x,y);
+      context.set_fill_style(&colour(cell)[1]);
+      context.fill_rect(inner_x,inner_y,inner_w,inner_h);
+    }
+    draw_portal_id(board,context,vp,theme,use_shapes,x,y);
+    match body.iter().position(|&segment| segment == (x,y)) {
+      Some(i) if i > 0 => draw_segment(board,context,vp,theme,&body,i),
+      _                => {}
+    }
+  }
+  if let Some(head) = board.query_head_location() {
+    if cells.contains(&head) {draw_head(board,context,vp,theme);}
+  }
+  let on_edge = |&(x,y):&(usize,usize)| {
+    x == 0 || y == 0 || x == GRID_W-1 || y == GRID_H-1
+  };
+  if cells.iter().any(on_edge) {draw_edges(board,context,vp,theme);}
+}
+
+// Width of segment i of a snake of the given length, as a fraction of a cell.
+// The head and body are full width and the last few segments taper off.
+fn segment_width(len:usize, i:usize) -> f64 {
+  let from_tail = len - 1 - i;
+  if i == 0 || from_tail >= TAPER_SEGMENTS {return BODY_WIDTH}
+  TAIL_WIDTH + (BODY_WIDTH - TAIL_WIDTH) * from_tail as f64
+    / TAPER_SEGMENTS as f64
+}
+
+fn opposite(dir:Direction) -> Direction {
+  match dir {
+    Direction::Up => Direction::Dn,
+    Direction::Dn => Direction::Up,
+    Direction::Lf => Direction::Rt,
+    Direction::Rt => Direction::Lf
+  }
+}
+
+// Draws segment i of the body as a rounded band from the middle of its cell
+// out to the edges it shares with the segments either side. Joins follow the
+// directions stored in the cells rather than positions, so a snake crossing a
+// wrapping edge or a portal leaves and arrives through the right edges.
+fn draw_segment(
+  board:&Board,
+  context:&impl Renderer,
+  vp:&Viewport,
+  theme:&Theme,
+  body:&[(usize,usize)],
+  i:usize
+) {
+  let direction = |(x,y):(usize,usize)| match board.peek(x,y) {
+    GridCell::Snake(_,dir) => dir,
+    _                      => panic!("Snake body is out of sync with board")
+  };
+  let size = vp.cell_w.min(vp.cell_h);
+  let width = segment_width(body.len(),i) * size;
+  let edge_width = |j| (width + segment_width(body.len(),j) * size) / 2.0;
+  // (direction of the join, width of the band where it meets the edge)
+  let mut joins = Vec::with_capacity(2);
+  if i > 0 {joins.push((direction(body[i]),edge_width(i-1)))}
+  if i+1 < body.len() {
+    joins.push((opposite(direction(body[i+1])),edge_width(i+1)));
+  }
+
+  let (x,y) = body[i];
+  let (mid_x,mid_y) =
+    ((x as f64 + 0.5) * vp.cell_w,(y as f64 + 0.5) * vp.cell_h);
+  let colours = theme.colour(
+    GridCell::Snake(0,Direction::Rt),i == 0,board.query_grace()
+  );
+  for (colour,scale) in [(&colours[0],1.0),(&colours[1],INNER_BAND)] {
+    context.set_fill_style(colour);
+    for &(dir,edge) in &joins {
+      // Unit vector along the join and half the cell in that direction.
+      let ((along_x,along_y),reach) = match dir {
+        Direction::Up => ((0.0,-1.0),vp.cell_h / 2.0),
+        Direction::Dn => ((0.0, 1.0),vp.cell_h / 2.0),
+        Direction::Lf => ((-1.0,0.0),vp.cell_w / 2.0),
+        Direction::Rt => (( 1.0,0.0),vp.cell_w / 2.0)
+      };
+      let (across_x,across_y) = (along_y,along_x);
+      let (near,far) = (width * scale / 2.0,edge * scale / 2.0);
+      let (end_x,end_y) = (mid_x + along_x * reach,mid_y + along_y * reach);
+      context.begin_path();
+      context.move_to(mid_x - across_x * near,mid_y - across_y * near);
+      context.line_to(end_x - across_x * far,end_y - across_y * far);
+      context.line_to(end_x + across_x * far,end_y + across_y * far);
+      context.line_to(mid_x + across_x * near,mid_y + across_y * near);
+      context.fill();
+    }
+    context.begin_path();
+    context.arc(mid_x,mid_y,width * scale / 2.0,0.0,std::f64::consts::TAU);
+    context.fill();
+  }
+}
+
+// Paints the cell at (x,y) as empty whatever is on it, for effects that take
+// things off the board before the board itself has caught up.
+pub fn clear_cell(
+  board:&Board,
+  context:&impl Renderer,
+  vp:&Viewport,
+  theme:&Theme,
+  x:usize,
+  y:usize
+) {
+  let colour = theme.colour(GridCell::Nothing,false,board.query_grace());
+  context.set_fill_style(&colour[0]);
+  context.fill_rect(x as f64 * vp.cell_w,y as f64 * vp.cell_h,
+    vp.cell_w,vp.cell_h);
+  let scale = 0.375f64.sqrt();
+  context.set_fill_style(&colour[1]);
+  context.fill_rect(
+    x as f64 * vp.cell_w + 0.5 * vp.cell_w * (1f64 - scale),
+    y as f64 * vp.cell_h + 0.5 * vp.cell_h * (1f64 - scale),
+    vp.cell_w * scale,
+    vp.cell_h * scale
+  );
+}
+
+// Draw the head of the snake in the snake head colours, with eyes looking the
+// way it is travelling.
+fn draw_head(
+  board:&Board,
+  context:&impl Renderer,
+  vp:&Viewport,
+  theme:&Theme
+) {
+  let colour = |cell,is_head| theme.colour(cell,is_head,board.query_grace());
+  let (cell_w,cell_h) = (vp.cell_w,vp.cell_h);
+  let (x,y) = board.query_head_location()
+    .expect_throw("Failed to locate head");
+  let body:Vec<(usize,usize)> = board.query_body().collect();
+  draw_segment(board,context,vp,theme,&body,0);
+  // Eyes on the head, pushed towards the facing direction.
+  let eyes = match board.peek(x,y) {
+    GridCell::Snake(_,Direction::Up) => [(0.3,0.3),(0.7,0.3)],
+    GridCell::Snake(_,Direction::Dn) => [(0.3,0.7),(0.7,0.7)],
+    GridCell::Snake(_,Direction::Lf) => [(0.3,0.3),(0.3,0.7)],
+    _                                => [(0.7,0.3),(0.7,0.7)]
+  };
+  context.set_fill_style(&colour(GridCell::Nothing,false)[0]);
+  for (eye_x,eye_y) in eyes {
+    context.begin_path();
+    context.arc((x as f64 + eye_x) * cell_w,(y as f64 + eye_y) * cell_h,
+      cell_w.min(cell_h) / 10.0,0.0,std::f64::consts::TAU);
+    context.fill();
+  }
+  // Count down the grace ticks left on the head while in grace.
+  if board.query_grace() {
+    context.set_fill_style(&colour(GridCell::Wall,false)[1]);
+    context.set_font(format!("bold {}px monospace",cell_h).as_str());
+    context.set_text_align("center");
+    context.set_text_baseline("middle");
+    context.fill_text(
+      board.query_grace_remaining().to_string().as_str(),
+      x as f64 * cell_w + cell_w / 2.0,
+      y as f64 * cell_h + cell_h / 2.0
+    );
+  }
+}
+
+// Mark the edges: dashed where they wrap, solid where they collide.
+fn draw_edges(
+  board:&Board,
+  context:&impl Renderer,
+  vp:&Viewport,
+  theme:&Theme
+) {
+  let colour = |cell| theme.colour(cell,false,board.query_grace());
+  let mark = vp.cell_h / 8.0;
+  let (horizontal,vertical) = match board.query_edge_mode() {
+    EdgeMode::Wrap   => (GridCell::Nothing,GridCell::Nothing),
+    EdgeMode::Solid  => (GridCell::Wall,GridCell::Wall),
+    EdgeMode::Mirror => (GridCell::Nothing,GridCell::Portal(0))
+  };
+  for (edge_type,is_vertical) in [(horizontal,false),(vertical,true)] {
+    let dashed = edge_type != GridCell::Wall;
+    let cell_count = if is_vertical {GRID_H} else {GRID_W};
+    let cell_len = if is_vertical {vp.cell_h} else {vp.cell_w};
+    context.set_fill_style(&colour(edge_type)[1]);
+    for i in (0..cell_count).filter(|i| !dashed || i%2 == 0) {
+      let start = i as f64 * cell_len;
+      if is_vertical {
+        context.fill_rect(0.0,start,mark,cell_len);
+        context.fill_rect(vp.width() - mark,start,mark,cell_len);
+      } else {
+        context.fill_rect(start,0.0,cell_len,mark);
+        context.fill_rect(start,vp.height() - mark,cell_len,mark);
+      }
+    }
+  }
+}
+
+const MENU_SHADE:f64 = 0.8; // opacity of the backdrop behind menus
+const BAR_WIDTH:f64 = 12.0;  // progress bar length, in cells
+
+// A line of information on a menu screen.
+pub enum Line {
+  Text(String),
+  Progress(String,f64), // labelled bar, filled from 0 to 1
+}
+
+// Draws a menu screen over the board: a title, some lines of information and
+// a list of choices with the selected one highlighted.
+pub fn draw_menu(
+  context:&impl Renderer,
+  vp:&Viewport,
+  theme:&Theme,
+  title:&str,
+  lines:&[Line],
+  items:&[String],
+  selected:usize
+) {
+  let colour = |cell,is_head| theme.colour(cell,is_head,false);
+  let text = &theme.colour(GridCell::Wall,false,true)[1];
+  let (mid_x,cell_h) = (vp.width() / 2.0,vp.cell_h);
+  context.set_global_alpha(MENU_SHADE);
+  context.set_fill_style(&colour(GridCell::Nothing,false)[0]);
+  context.fill_rect(0.0,0.0,vp.width(),vp.height());
+  context.set_global_alpha(1.0);
+  context.set_text_align("center");
+  context.set_text_baseline("middle");
+
+  let mut y = cell_h * 2.5;
+  context.set_fill_style(&colour(GridCell::Snake(0,Direction::Rt),true)[1]);
+  context.set_font(format!("bold {}px monospace",cell_h * 1.5).as_str());
+  context.fill_text(title,mid_x,y);
+  y += cell_h * 1.5;
+  context.set_fill_style(text);
+  context.set_font(format!("{}px monospace",cell_h * 0.6).as_str());
+  for line in lines {
+    match line {
+      Line::Text(text) => {
+        context.fill_text(text,mid_x,y);
+        y += cell_h * 0.8;
+      },
+      Line::Progress(label,progress) => {
+        context.fill_text(label,mid_x,y);
+        y += cell_h * 0.6;
+        let width = BAR_WIDTH * vp.cell_w;
+        let (left,height) = (mid_x - width / 2.0,cell_h * 0.3);
+        let filled = width * progress.clamp(0.0,1.0);
+        context.set_fill_style(&colour(GridCell::Nothing,false)[1]);
+        context.fill_rect(left,y,width,height);
+        let snake = GridCell::Snake(0,Direction::Rt);
+        context.set_fill_style(&colour(snake,false)[1]);
+        context.fill_rect(left,y,filled,height);
+        context.set_fill_style(text);
+        y += cell_h * 0.9;
+      }
+    }
+  }
+  y += cell_h * 0.5;
+  // Long lists are packed closer together to fit above the bottom edge.
+  let room = vp.height() - y - cell_h * 0.5;
+  let spacing = (cell_h * 0.9).min(room / items.len().max(1) as f64);
+  context.set_font(format!("bold {}px monospace",cell_h * 0.7).as_str());
+  for (i,item) in items.iter().enumerate() {
+    if i == selected {
+      context.set_fill_style(&colour(GridCell::Apple(0),false)[1]);
+      context.fill_text(&format!("> {item} <"),mid_x,y);
+      context.set_fill_style(text);
+    } else {
+      context.fill_text(item,mid_x,y);
+    }
+    y += spacing;
+  }
+}
+
+// Gives every cell type a distinct shape so nothing relies on colour alone:
+// apples are circles, walls are hatched and portals are rings. The snake is
+// drawn by draw_segment and draw_head.
+fn draw_shape(
+  board:&Board,
+  context:&impl Renderer,
+  vp:&Viewport,
+  theme:&Theme,
+  x:usize,
+  y:usize
+) {
+  let colour = |cell| theme.colour(cell,false,board.query_grace());
+  let (w,h) = (vp.cell_w,vp.cell_h);
+  let radius = w.min(h) / 2.0;
+  let (left,top) = (x as f64 * w, y as f64 * h);
+  let (mid_x,mid_y) = (left + w / 2.0, top + h / 2.0);
+  context.set_line_width(radius / 5.0);
+  match board.peek(x,y) {
+    cell@GridCell::Apple(t) => {
+      context.set_fill_style(&colour(cell)[1]);
+      context.begin_path();
+      context.arc(mid_x,mid_y,
+        radius * 0.7 * board.query_apple_freshness(t).max(0.2),
+        0.0,std::f64::consts::TAU);
+      context.fill();
+    },
+    GridCell::Wall => {
+      context.set_stroke_style(&colour(GridCell::Wall)[1]);
+      context.begin_path();
+      for [(x0,y0),(x1,y1)] in [
+        [(0.0,0.5),(0.5,0.0)], [(0.0,1.0),(1.0,0.0)], [(0.5,1.0),(1.0,0.5)]
+      ] {
+        context.move_to(left + x0 * w,top + y0 * h);
+        context.line_to(left + x1 * w,top + y1 * h);
+      }
+      context.stroke();
+    },
+    cell@GridCell::Portal(_) => {
+      context.set_stroke_style(&colour(cell)[1]);
+      context.begin_path();
+      context.arc(mid_x,mid_y,radius * 0.6,0.0,std::f64::consts::TAU);
+      context.stroke();
+    },
+    _ => {}
+  }
+}
+
+// Numbers a portal with the digit of its pair in the level layout, so the
+// portal it leads to can be told apart from any others.
+fn draw_portal_id(
+  board:&Board,
+  context:&impl Renderer,
+  vp:&Viewport,
+  theme:&Theme,
+  use_shapes:bool,
+  x:usize,
+  y:usize
+) {
+  let cell@GridCell::Portal(id) = board.peek(x,y) else {return};
+  let colour = theme.colour(cell,false,board.query_grace());
+  // The outer colour over the inner square, or the ring's colour inside it.
+  context.set_fill_style(&colour[if use_shapes {1} else {0}]);
+  context.set_font(format!("bold {}px monospace",vp.cell_h / 2.0).as_str());
+  context.set_text_align("center");
+  context.set_text_baseline("middle");
+  context.fill_text(
+    &id.to_string(),
+    (x as f64 + 0.5) * vp.cell_w,
+    (y as f64 + 0.5) * vp.cell_h
+  );
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::levels::LEVELS;
+  use crate::rand::Rng;
+  use crate::rules::GameRules;
+  use std::cell::{Cell,RefCell};
+  use std::time::{Duration,Instant};
+
+  // Writes down every draw call as a line of text.
+  #[derive(Default)]
+  struct Recorder {
+    commands: RefCell<Vec<String>>
+  }
+
+  impl Recorder {
+    fn record(&self, command:String) {
+      self.commands.borrow_mut().push(command);
+    }
+  }
+
+  impl Renderer for Recorder {
+    fn set_fill_style(&self, style:&str) {
+      self.record(format!("fill_style {style}"))
+    }
+    fn set_stroke_style(&self, style:&str) {
+      self.record(format!("stroke_style {style}"))
+    }
+    fn set_line_width(&self, width:f64) {
+      self.record(format!("line_width {width:.2}"))
+    }
+    fn set_global_alpha(&self, alpha:f64) {
+      self.record(format!("global_alpha {alpha:.2}"))
+    }
+    fn set_font(&self, font:&str) {self.record(format!("font {font}"))}
+    fn set_text_align(&self, align:&str) {
+      self.record(format!("text_align {align}"))
+    }
+    fn set_text_baseline(&self, baseline:&str) {
+      self.record(format!("text_baseline {baseline}"))
+    }
+    fn fill_rect(&self, x:f64, y:f64, w:f64, h:f64) {
+      self.record(format!("fill_rect {x:.2} {y:.2} {w:.2} {h:.2}"))
+    }
+    fn fill_text(&self, text:&str, x:f64, y:f64) {
+      self.record(format!("fill_text {text:?} {x:.2} {y:.2}"))
+    }
+    fn begin_path(&self) {self.record("begin_path".into())}
+    fn move_to(&self, x:f64, y:f64) {
+      self.record(format!("move_to {x:.2} {y:.2}"))
+    }
+    fn line_to(&self, x:f64, y:f64) {
+      self.record(format!("line_to {x:.2} {y:.2}"))
+    }
+    fn arc(&self, x:f64, y:f64, radius:f64, start:f64, end:f64) {
+      self.record(format!("arc {x:.2} {y:.2} {radius:.2} {start:.2} {end:.2}"))
+    }
+    fn fill(&self) {self.record("fill".into())}
+    fn stroke(&self) {self.record("stroke".into())}
+  }
+
+  // Compares what was drawn against src/snapshots/<name>.snap. Run the tests
+  // with UPDATE_SNAPSHOTS=1 to (re)write the snapshots after a change.
+  fn assert_snapshot(name:&str, recorder:&Recorder) {
+    let path = format!(
+      "{}/src/snapshots/{name}.snap",
+      env!("CARGO_MANIFEST_DIR")
+    );
+    let drawn = recorder.commands.borrow().join("\n") + "\n";
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+      std::fs::write(&path,drawn).unwrap();
+      return;
+    }
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+      panic!("No snapshot at {path}, run with UPDATE_SNAPSHOTS=1 to make it")
+    });
+    assert_eq!(drawn,expected,"{name} drew differently to its snapshot");
+  }
+
+  const VIEWPORT:Viewport = Viewport::init(); // 40px cells, 1200x800 canvas
+
+  fn theme() -> Theme {
+    Theme::built_ins("./assets/").remove(0)
+  }
+
+  // A snake heading right with its head at (7,5) and body at (5,5),(6,5),
+  // walls round the edge, apples at (10,10) and (11,10), and the level's
+  // portals at (3,2),(26,2),(3,17),(26,17).
+  fn board(rules:GameRules) -> Board {
+    let mut board = Board::new(GameRules {level: 1, ..rules});
+    board.load_level(&LEVELS[1]).unwrap();
+    board.generate_walls()
+      .place_snake(5,5,3,Direction::Rt)
+      .place_apple(10,10)
+      .place_apple(11,10);
+    board.do_game_tick();
+    board.do_game_tick();
+    board
+  }
+
+  fn draw(board:&Board, use_shapes:bool, cells:&[(usize,usize)]) -> Recorder {
+    let recorder = Recorder::default();
+    draw_cells(board,&recorder,&VIEWPORT,&theme(),use_shapes,cells);
+    recorder
+  }
+
+  #[test]
+  fn nothing_cell() {
+    let board = board(GameRules::init());
+    assert_snapshot("nothing_cell",&draw(&board,false,&[(15,15)]));
+  }
+
+  #[test]
+  fn snake_body_cells() {
+    let board = board(GameRules::init());
+    assert_snapshot("snake_body_cells",&draw(&board,false,&[(5,5),(6,5)]));
+  }
+
+  #[test]
+  fn head_overlay() {
+    let board = board(GameRules::init());
+    assert_snapshot("head_overlay",&draw(&board,false,&[(7,5)]));
+  }
+
+  #[test]
+  fn snake_joins_across_wrapped_edge() {
+    let mut board = Board::new(GameRules::init());
+    board.place_snake(28,5,3,Direction::Rt);
+    board.do_game_tick();
+    board.do_game_tick();
+    assert_snapshot("snake_wrapped",&draw(&board,false,&[(29,5),(0,5)]));
+  }
+
+  #[test]
+  fn apple_cell() {
+    let board = board(GameRules::init());
+    assert_snapshot("apple_cell",&draw(&board,false,&[(10,10)]));
+  }
+
+  #[test]
+  fn expiring_apple_cell() {
+    let board = board(GameRules {expiring_apples: true, ..GameRules::init()});
+    assert_snapshot("expiring_apple_cell",&draw(&board,false,&[(10,10)]));
+  }
+
+  #[test]
+  fn wall_and_portal_cells() {
+    let board = board(GameRules::init());
+    assert_snapshot("wall_and_portal_cells",&draw(&board,false,&[(3,2),(0,0)]));
+  }
+
+  #[test]
+  fn portal_pairs_numbered() {
+    let board = board(GameRules::init());
+    assert_snapshot(
+      "portal_pairs",
+      &draw(&board,false,&[(3,2),(26,17),(26,2),(3,17)])
+    );
+  }
+
+  #[test]
+  fn shapes() {
+    let board = board(GameRules::init());
+    assert_snapshot(
+      "shapes",
+      &draw(&board,true,&[(10,10),(3,2),(15,19),(7,5)])
+    );
+  }
+
+  #[test]
+  fn grace_palette() {
+    let mut board = Board::new(GameRules::init());
+    board.generate_walls().place_snake(1,5,1,Direction::Lf);
+    assert!(matches!(board.do_game_tick(),GameEvent::GraceTick));
+    assert_snapshot("grace_palette",&draw(&board,false,&[(2,5),(1,5)]));
+  }
+
+  #[test]
+  fn edges_redrawn_with_edge_cells() {
+    let rules = GameRules {edge_mode: EdgeMode::Mirror, ..GameRules::init()};
+    let board = board(rules);
+    assert_snapshot("edges_mirror",&draw(&board,false,&[(0,5)]));
+  }
+
+  #[test]
+  fn menu_overlay() {
+    let recorder = Recorder::default();
+    draw_menu(
+      &recorder,
+      &VIEWPORT,
+      &theme(),
+      "Game Over",
+      &[Line::Text("Score: 150".into())],
+      &["Retry".into(),"Main Menu".into()],
+      1
+    );
+    assert_snapshot("menu_overlay",&recorder);
+  }
+
+  #[test]
+  fn long_menu_fits_on_the_canvas() {
+    let recorder = Recorder::default();
+    let items = (0..30).map(|i| format!("Item {i}")).collect::<Vec<_>>();
+    draw_menu(&recorder,&VIEWPORT,&theme(),"Settings",&[],&items,0);
+    let commands = recorder.commands.borrow();
+    let last = commands.iter().rfind(|c| c.starts_with("fill_text \"Item 29\""))
+      .unwrap();
+    let y:f64 = last.rsplit(' ').next().unwrap().parse().unwrap();
+    assert!(y < VIEWPORT.height() - VIEWPORT.cell_h / 2.0);
+  }
+
+  #[test]
+  fn progress_lines() {
+    let recorder = Recorder::default();
+    draw_menu(
+      &recorder,
+      &VIEWPORT,
+      &theme(),
+      "Achievements",
+      &[Line::Progress("Close Calls 4/10".into(),0.4)],
+      &["Back".into()],
+      0
+    );
+    assert_snapshot("progress_lines",&recorder);
+  }
+
+  #[test]
+  fn full_redraw_paints_background_first() {
+    let board = board(GameRules::init());
+    let recorder = Recorder::default();
+    draw_board(&board,&recorder,&VIEWPORT,&theme(),false,None);
+    let commands = recorder.commands.borrow();
+    assert_eq!(commands[0],"fill_style #1d2021");
+    assert_eq!(commands[1],"fill_rect 0.00 0.00 1200.00 800.00");
+    assert!(commands.contains(&"fill_style #689d6a".to_string()));
+  }
+
+  #[test]
+  fn ghost_drawn_faded_under_the_snake() {
+    let board = board(GameRules::init());
+    let mut ghost = Board::new(GameRules::init());
+    ghost.place_snake(20,15,1,Direction::Up);
+    let recorder = Recorder::default();
+    draw_board(&board,&recorder,&VIEWPORT,&theme(),false,Some(&ghost));
+    let commands = recorder.commands.borrow();
+    let position = |command:&str| {
+      commands.iter().position(|c| c.starts_with(command)).unwrap()
+    };
+    let faded = position("global_alpha 0.35");
+    assert!(faded < position("arc 820.00 620.00"));  // ghost head
+    assert!(position("arc 820.00 620.00") < position("global_alpha 1.00"));
+    assert!(position("global_alpha 1.00") < position("arc 300.00 220.00"));
+  }
+
+  // Paints into a pixmap in software, so the benchmark below pays for filling
+  // pixels as well as for issuing draw calls. Text isn't rasterised.
+  struct Raster {
+    pixmap: RefCell<tiny_skia::Pixmap>,
+    path: RefCell<tiny_skia::PathBuilder>,
+    fill: Cell<tiny_skia::Color>,
+    stroke: Cell<tiny_skia::Color>,
+    line_width: Cell<f32>,
+    alpha: Cell<f32>,
+  }
+
+  impl Raster {
+    fn new(vp:&Viewport) -> Self {
+      Raster {
+        pixmap: RefCell::new(
+          tiny_skia::Pixmap::new(vp.width() as u32,vp.height() as u32).unwrap()
+        ),
+        path: RefCell::new(tiny_skia::PathBuilder::new()),
+        fill: Cell::new(tiny_skia::Color::BLACK),
+        stroke: Cell::new(tiny_skia::Color::BLACK),
+        line_width: Cell::new(1.0),
+        alpha: Cell::new(1.0),
+      }
+    }
+
+    // Only the #rrggbb colours the built-in themes use.
+    fn colour(style:&str) -> tiny_skia::Color {
+      let channel = |i:usize| u8::from_str_radix(&style[i..i+2],16).unwrap();
+      tiny_skia::Color::from_rgba8(channel(1),channel(3),channel(5),255)
+    }
+
+    fn paint(&self, colour:tiny_skia::Color) -> tiny_skia::Paint<'static> {
+      let mut paint = tiny_skia::Paint::default();
+      let mut colour = colour;
+      colour.apply_opacity(self.alpha.get());
+      paint.set_color(colour);
+      paint.anti_alias = true;
+      paint
+    }
+
+    // Like the canvas, the path is kept after it's drawn until begin_path.
+    fn take_path(&self) -> Option<tiny_skia::Path> {
+      self.path.borrow().clone().finish()
+    }
+  }
+
+  impl Renderer for Raster {
+    fn set_fill_style(&self, style:&str) {self.fill.set(Raster::colour(style))}
+    fn set_stroke_style(&self, style:&str) {
+      self.stroke.set(Raster::colour(style))
+    }
+    fn set_line_width(&self, width:f64) {self.line_width.set(width as f32)}
+    fn set_global_alpha(&self, alpha:f64) {self.alpha.set(alpha as f32)}
+    fn set_font(&self, _font:&str) {}
+    fn set_text_align(&self, _align:&str) {}
+    fn set_text_baseline(&self, _baseline:&str) {}
+    fn fill_rect(&self, x:f64, y:f64, w:f64, h:f64) {
+      let (x,y,w,h) = (x as f32,y as f32,w as f32,h as f32);
+      if let Some(rect) = tiny_skia::Rect::from_xywh(x,y,w,h) {
+        self.pixmap.borrow_mut().fill_rect(
+          rect,&self.paint(self.fill.get()),
+          tiny_skia::Transform::identity(),None
+        );
+      }
+    }
+    fn fill_text(&self, _text:&str, _x:f64, _y:f64) {}
+    fn begin_path(&self) {
+      self.path.replace(tiny_skia::PathBuilder::new());
+    }
+    fn move_to(&self, x:f64, y:f64) {
+      self.path.borrow_mut().move_to(x as f32,y as f32)
+    }
+    fn line_to(&self, x:f64, y:f64) {
+      self.path.borrow_mut().line_to(x as f32,y as f32)
+    }
+    // Every arc drawn is a whole circle.
+    fn arc(&self, x:f64, y:f64, radius:f64, _start:f64, _end:f64) {
+      self.path.borrow_mut().push_circle(x as f32,y as f32,radius as f32)
+    }
+    fn fill(&self) {
+      if let Some(path) = self.take_path() {
+        self.pixmap.borrow_mut().fill_path(
+          &path,&self.paint(self.fill.get()),tiny_skia::FillRule::Winding,
+          tiny_skia::Transform::identity(),None
+        );
+      }
+    }
+    fn stroke(&self) {
+      if let Some(path) = self.take_path() {
+        let stroke = tiny_skia::Stroke {
+          width: self.line_width.get(), ..Default::default()
+        };
+        self.pixmap.borrow_mut().stroke_path(
+          &path,&self.paint(self.stroke.get()),&stroke,
+          tiny_skia::Transform::identity(),None
+        );
+      }
+    }
+  }
+
+  // A software-raster stand-in for ?bench, timing the original renderer, a
+  // full redraw and a dirty-cell redraw over the same randomly steered game:
+  // cargo test --release raster_benchmark -- --ignored --nocapture
+  #[test]
+  #[ignore]
+  fn raster_benchmark() {
+    const FRAMES:u32 = 2000;
+    let rules = GameRules::init();
+    let new_board = |seed:u64| {
+      let mut board = Board::seeded(rules,seed);
+      board.load_level(&LEVELS[rules.level]).unwrap();
+      if rules.build_walls {board.generate_walls();}
+      board.generate_snake().unwrap();
+      board.fill_apples().unwrap();
+      board
+    };
+    let (raster,theme) = (Raster::new(&VIEWPORT),theme());
+    let directions = [Direction::Up,Direction::Rt,Direction::Dn,Direction::Lf];
+    let mut rng = Rng::new(35);
+    let mut board = new_board(0);
+    let mut elapsed = [Duration::ZERO; 3];
+    for frame in 0..FRAMES {
+      if frame % 5 == 0 {
+        board.change_facing_direction(directions[rng.usize(0,4)]);
+      }
+      if let GameEvent::GameOver(_) = board.do_game_tick() {
+        board = new_board(frame as u64);
+      }
+      let start = Instant::now();
+      draw_board_original(&board,&raster,&VIEWPORT,&theme);
+      let original = Instant::now();
+      draw_board(&board,&raster,&VIEWPORT,&theme,false,None);
+      let full = Instant::now();
+      let cells = board.take_dirty_cells();
+      draw_cells(&board,&raster,&VIEWPORT,&theme,false,&cells);
+      let dirty = Instant::now();
+      elapsed[0] += original - start;
+      elapsed[1] += full - original;
+      elapsed[2] += dirty - full;
+    }
+    let per_frame = |d:Duration| d.as_secs_f64() * 1000.0 / FRAMES as f64;
+    println!(
+      "original: {:.4}ms/frame, full redraw: {:.4}ms/frame, \
+        dirty cells: {:.4}ms/frame",
+      per_frame(elapsed[0]),per_frame(elapsed[1]),per_frame(elapsed[2])
+    );
+  }
+}
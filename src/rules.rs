@@ -0,0 +1,140 @@
+use crate::game_logic::{EdgeMode,GRID_H,GRID_W,MAX_APPLES,MAX_GRACE_TICKS};
+use crate::levels::LEVELS;
+use wasm_bindgen::prelude::*;
+
+const MIN_TICK_RATE:f32 = 1.0;
+const MAX_TICK_RATE:f32 = 60.0;
+const MAX_LENGTH:usize = GRID_W*GRID_H;  // the snake can't outgrow the board
+const MAX_SCORE_PER_APPLE:u32 = 10_000; // keeps the score well inside a u32
+const MAX_FULL_BOARD_BONUS:u32 = 1_000_000;
+
+// Everything that changes how a game plays. Rules are fixed for the length of
+// a game, and each distinct set of rules keeps its own high score.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GameRules {
+  #[wasm_bindgen(js_name = startingLength)]
+  pub starting_length: usize, // length is one-based, ∴ len > 0
+  #[wasm_bindgen(js_name = growthPerApple)]
+  pub growth_per_apple: usize,
+  #[wasm_bindgen(js_name = scorePerApple)]
+  pub score_per_apple: u32,
+  #[wasm_bindgen(js_name = fullBoardBonus)]
+  pub full_board_bonus: u32,
+  #[wasm_bindgen(js_name = tickRate)]
+  pub tick_rate: f32,         // board is ticked (snake moves) on every tick
+  #[wasm_bindgen(js_name = graceTicks)]
+  pub grace_ticks: usize,
+  #[wasm_bindgen(js_name = buildWalls)]
+  pub build_walls: bool,
+  pub level: usize,
+  #[wasm_bindgen(js_name = appleTarget)]
+  pub apple_target: usize,
+  pub feast: bool,
+  #[wasm_bindgen(js_name = expiringApples)]
+  pub expiring_apples: bool,
+  #[wasm_bindgen(js_name = edgeMode)]
+  pub edge_mode: EdgeMode,
+}
+
+#[wasm_bindgen]
+impl GameRules {
+  #[wasm_bindgen(constructor)]
+  pub fn standard() -> Self {
+    GameRules::init()
+  }
+}
+
+impl GameRules {
+  pub const fn init() -> Self {
+    GameRules {
+      starting_length: 4,
+      growth_per_apple: 3,
+      score_per_apple: 50,
+      full_board_bonus: 2500,
+      tick_rate: 12.0,
+      grace_ticks: 1,
+      build_walls: true,
+      level: 0,
+      apple_target: 1,
+      feast: false,
+      expiring_apples: false,
+      edge_mode: EdgeMode::Wrap,
+    }
+  }
+
+  // Pulls every field back into the range the game can handle.
+  pub fn validated(self) -> Self {
+    GameRules {
+      starting_length: self.starting_length.clamp(1,MAX_LENGTH),
+      growth_per_apple: self.growth_per_apple.min(MAX_LENGTH),
+      score_per_apple: self.score_per_apple.min(MAX_SCORE_PER_APPLE),
+      full_board_bonus: self.full_board_bonus.min(MAX_FULL_BOARD_BONUS),
+      tick_rate: self.tick_rate.clamp(MIN_TICK_RATE,MAX_TICK_RATE),
+      grace_ticks: self.grace_ticks.min(MAX_GRACE_TICKS),
+      level: self.level.min(LEVELS.len()-1),
+      apple_target: self.apple_target.clamp(1,MAX_APPLES),
+      ..self
+    }
+  }
+
+  // Short names for everything this game is played with, for showing to the
+  // player.
+  pub fn modifiers(&self) -> Vec<String> {
+    let mut modifiers = vec![format!("Level {}",self.level + 1)];
+    if self.build_walls {modifiers.push("Walls".into())}
+    modifiers.push(format!("{:?} Edges",self.edge_mode));
+    if self.apple_target > 1 {
+      modifiers.push(format!("{} Apples",self.apple_target))
+    }
+    if self.feast {modifiers.push("Feast".into())}
+    if self.expiring_apples {modifiers.push("Expiring Apples".into())}
+    if self.grace_ticks > 0 {
+      modifiers.push(format!("{} Grace",self.grace_ticks))
+    }
+    modifiers
+  }
+
+  // FNV-1a hash of every field, stable between builds and sessions.
+  pub fn bucket(&self) -> u32 {
+    [
+      self.starting_length as u64,
+      self.growth_per_apple as u64,
+      self.score_per_apple as u64,
+      self.full_board_bonus as u64,
+      self.tick_rate.to_bits() as u64,
+      self.grace_ticks as u64,
+      self.build_walls as u64,
+      self.level as u64,
+      self.apple_target as u64,
+      self.feast as u64,
+      self.expiring_apples as u64,
+      self.edge_mode as u64,
+    ].iter()
+      .flat_map(|field| field.to_le_bytes())
+      .fold(0x811c9dc5u32, |hash,byte| {
+        (hash ^ byte as u32).wrapping_mul(0x01000193)
+      })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn validated_clamps_values_that_would_overflow() {
+    let rules = GameRules {
+      starting_length: usize::MAX,
+      growth_per_apple: usize::MAX,
+      score_per_apple: u32::MAX,
+      full_board_bonus: u32::MAX,
+      ..GameRules::init()
+    }.validated();
+    assert_eq!(rules.starting_length,MAX_LENGTH);
+    assert_eq!(rules.growth_per_apple,MAX_LENGTH);
+    assert_eq!(rules.score_per_apple,MAX_SCORE_PER_APPLE);
+    assert_eq!(rules.full_board_bonus,MAX_FULL_BOARD_BONUS);
+    assert_eq!(GameRules::init().validated(),GameRules::init());
+  }
+}
@@ -0,0 +1,143 @@
+use crate::game_logic::{Board,Death};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Running totals over a number of finished games.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Totals {
+  pub games_played: BTreeMap<String,u32>, // by mode, see GameRules::modifiers
+  pub apples: u32,
+  pub longest_snake: usize,
+  pub ticks: u64,
+  pub deaths: BTreeMap<String,u32>,       // by cause
+  pub grace_saves: u32,
+  pub total_score: u64,
+}
+
+// What gets handed to JS, the totals along with what's worked out from them.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Summary<'a> {
+  #[serde(flatten)]
+  totals: &'a Totals,
+  games: u32,
+  average_score: f64,
+}
+
+impl Totals {
+  const fn init() -> Self {
+    Totals {
+      games_played: BTreeMap::new(),
+      apples: 0,
+      longest_snake: 0,
+      ticks: 0,
+      deaths: BTreeMap::new(),
+      grace_saves: 0,
+      total_score: 0,
+    }
+  }
+
+  fn record(&mut self, board:&Board, death:Option<Death>) {
+    let mode = board.query_rules().modifiers().join(", ");
+    *self.games_played.entry(mode).or_default() += 1;
+    self.apples += board.query_apples_eaten();
+    self.longest_snake = self.longest_snake.max(board.query_length());
+    self.ticks += board.query_ticks() as u64;
+    if let Some(death) = death {
+      *self.deaths.entry(format!("{:?}",death.cause)).or_default() += 1;
+    }
+    self.grace_saves += board.query_grace_saves();
+    self.total_score += board.query_score() as u64;
+  }
+
+  fn summary(&self) -> Summary<'_> {
+    let games = self.games_played.values().sum();
+    let average_score = if games == 0 {0.0}
+      else {self.total_score as f64 / games as f64};
+    Summary {totals: self, games, average_score}
+  }
+}
+
+// Lifetime stats are kept in storage between visits, session stats only last
+// as long as the page.
+pub struct Stats {
+  pub lifetime: Totals,
+  pub session: Totals,
+}
+
+impl Stats {
+  pub const fn init() -> Self {
+    Stats {lifetime: Totals::init(), session: Totals::init()}
+  }
+
+  pub fn record_game(&mut self, board:&Board, death:Option<Death>) {
+    self.lifetime.record(board,death);
+    self.session.record(board,death);
+  }
+
+  pub fn to_json(&self) -> String {
+    serde_json::json!({
+      "lifetime": self.lifetime.summary(),
+      "session": self.session.summary()
+    }).to_string()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::game_logic::*;
+  use crate::rules::GameRules;
+
+  // Runs a snake of the given length left into a wall.
+  fn finished_game(len:usize) -> (Board,Death) {
+    let mut board = Board::new(GameRules {grace_ticks: 0, ..GameRules::init()});
+    board.generate_walls().place_snake(2,5,len,Direction::Lf);
+    board.do_game_tick();
+    match board.do_game_tick() {
+      GameEvent::GameOver(death) => (board,death),
+      _ => panic!("Expected the snake to hit the wall")
+    }
+  }
+
+  #[test]
+  fn records_totals_for_lifetime_and_session() {
+    let mut stats = Stats::init();
+    let (board,death) = finished_game(3);
+    stats.record_game(&board,Some(death));
+    stats.record_game(&board,Some(death));
+    for totals in [&stats.lifetime,&stats.session] {
+      assert_eq!(totals.games_played.values().sum::<u32>(),2);
+      assert_eq!(totals.ticks,2);
+      assert_eq!(totals.longest_snake,2);
+      assert_eq!(totals.deaths.get("Wall"),Some(&2));
+    }
+  }
+
+  #[test]
+  fn abandoned_games_count_without_a_death() {
+    let mut stats = Stats::init();
+    let mut board = Board::new(GameRules::init());
+    board.place_snake(5,5,1,Direction::Rt);
+    board.do_game_tick();
+    stats.record_game(&board,None);
+    assert_eq!(stats.session.games_played.values().sum::<u32>(),1);
+    assert_eq!(stats.session.ticks,1);
+    assert!(stats.session.deaths.is_empty());
+  }
+
+  #[test]
+  fn json_has_averages_and_survives_a_round_trip() {
+    let mut stats = Stats::init();
+    let (board,death) = finished_game(1);
+    stats.record_game(&board,Some(death));
+    let json:serde_json::Value =
+      serde_json::from_str(&stats.to_json()).unwrap();
+    assert_eq!(json["session"]["games"],1);
+    assert_eq!(json["lifetime"]["averageScore"],0.0);
+    let lifetime:Totals =
+      serde_json::from_value(json["lifetime"].clone()).unwrap();
+    assert_eq!(lifetime,stats.lifetime);
+  }
+}
@@ -0,0 +1,33 @@
+// Thin wrapper over localStorage. Storage can be unavailable (private browsing,
+// pages opened from file://), in which case nothing is loaded or saved.
+
+const KEY_PREFIX:&str = "snakegame_wasm.";
+
+fn local_storage() -> Option<web_sys::Storage> {
+  web_sys::window()?.local_storage().ok()?
+}
+
+// The keys of one game. Games on the same page keep their own progress and
+// settings when each has its own namespace, the empty one is the standard.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Storage {
+  prefix:String,
+}
+
+impl Storage {
+  pub fn new(namespace:&str) -> Self {
+    let prefix = if namespace.is_empty() {KEY_PREFIX.to_string()}
+      else {format!("{KEY_PREFIX}{namespace}.")};
+    Storage {prefix}
+  }
+
+  pub fn load(&self, key:&str) -> Option<String> {
+    local_storage()?.get_item(&format!("{}{key}",self.prefix)).ok()?
+  }
+
+  pub fn save(&self, key:&str, value:&str) {
+    if let Some(storage) = local_storage() {
+      let _ = storage.set_item(&format!("{}{key}",self.prefix),value);
+    }
+  }
+}
@@ -0,0 +1,144 @@
+use crate::game_logic::GridCell;
+use serde::{Deserialize, Serialize};
+
+// Each entry is [outer colour, inner colour] as CSS colour strings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Palette {
+  pub nothing: [String;2],
+  pub snake: [String;2],
+  pub head: [String;2],
+  pub apple: [String;2],
+  pub wall: [String;2],
+  pub portal: [String;2]
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Theme {
+  pub name: String,
+  pub normal: Palette,
+  pub grace: Palette,
+  pub background: [String;2] // CSS backgrounds for [playing, game over]
+}
+
+impl Palette {
+  // Order is nothing, snake, head, apple, wall, portal.
+  fn from_strs(colours:[[&str;2];6]) -> Self {
+    let [nothing,snake,head,apple,wall,portal] =
+      colours.map(|pair| pair.map(String::from));
+    Palette {nothing,snake,head,apple,wall,portal}
+  }
+}
+
+impl Theme {
+  pub fn colour(&self, cell:GridCell, is_head:bool, grace:bool) -> &[String;2] {
+    let palette = if grace {&self.grace} else {&self.normal};
+    match cell {
+      GridCell::Nothing                => &palette.nothing,
+      GridCell::Snake(..) if is_head   => &palette.head,
+      GridCell::Snake(..)              => &palette.snake,
+      GridCell::Apple(_)               => &palette.apple,
+      GridCell::Wall                   => &palette.wall,
+      GridCell::Portal(_)              => &palette.portal
+    }
+  }
+
+  pub fn from_json(json:&str) -> Result<Theme,String> {
+    let theme:Theme = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    if theme.name.trim().is_empty() {return Err("Theme has no name".into())}
+    Ok(theme)
+  }
+
+  // Backgrounds are loaded from under assets, a URL ending in a slash.
+  pub fn built_ins(assets:&str) -> Vec<Theme> {
+    vec![
+      Theme {
+        name: "Gruvbox".into(),
+        normal: Palette::from_strs([
+          ["#1d2021","#282828"], ["#98971a","#b6b926"], ["#689d6a","#8ec07c"],
+          ["#cc241d","#f74833"], ["#3c3836","#50493c"], ["#b16286","#d3869b"]
+        ]),
+        grace: Palette::from_strs([
+          ["#282828","#32302f"], ["#79740e","#98971a"], ["#427b58","#689d6a"],
+          ["#9d0006","#cc241d"], ["#d5c6a1","#ebdbb2"], ["#8f3f71","#b16286"]
+        ]),
+        background: [
+          format!("url(\"{assets}backgrounds/dark.png\")"),
+          format!("url(\"{assets}backgrounds/light.png\")")
+        ]
+      },
+      Theme {
+        name: "Solarized".into(),
+        normal: Palette::from_strs([
+          ["#002b36","#073642"], ["#859900","#9cb300"], ["#2aa198","#35c9bd"],
+          ["#dc322f","#f2534f"], ["#586e75","#657b83"], ["#6c71c4","#8b8fd6"]
+        ]),
+        grace: Palette::from_strs([
+          ["#073642","#0a4454"], ["#6b7a00","#859900"], ["#1f7a73","#2aa198"],
+          ["#b02825","#dc322f"], ["#eee8d5","#fdf6e3"], ["#d33682","#e05a9d"]
+        ]),
+        background: ["#002b36".into(), "#fdf6e3".into()]
+      },
+      Theme {
+        name: "High Contrast".into(),
+        normal: Palette::from_strs([
+          ["#000000","#111111"], ["#00ff00","#ffffff"], ["#ffff00","#ffffff"],
+          ["#ff0000","#ffffff"], ["#ffffff","#bbbbbb"], ["#ff00ff","#ffffff"]
+        ]),
+        grace: Palette::from_strs([
+          ["#330000","#440000"], ["#00aa00","#ffffff"], ["#ffaa00","#ffffff"],
+          ["#aa0000","#ffffff"], ["#ff0000","#ffffff"], ["#aa00aa","#ffffff"]
+        ]),
+        background: ["#000000".into(), "#ffffff".into()]
+      },
+      Theme {
+        name: "Monochrome".into(),
+        normal: Palette::from_strs([
+          ["#101010","#181818"], ["#a0a0a0","#c8c8c8"], ["#e0e0e0","#ffffff"],
+          ["#ffffff","#606060"], ["#404040","#505050"], ["#707070","#909090"]
+        ]),
+        grace: Palette::from_strs([
+          ["#202020","#282828"], ["#808080","#a0a0a0"], ["#c0c0c0","#e0e0e0"],
+          ["#e0e0e0","#404040"], ["#d0d0d0","#e8e8e8"], ["#505050","#707070"]
+        ]),
+        background: ["#000000".into(), "#d0d0d0".into()]
+      },
+      // Colourblind-safe themes, built on the Okabe-Ito palette.
+      Theme {
+        name: "Deuteranopia".into(),
+        normal: Palette::from_strs([
+          ["#1a1a2e","#23233a"], ["#0072b2","#3a9ad9"], ["#56b4e9","#9fd6f5"],
+          ["#e69f00","#ffc14d"], ["#5a5a5a","#6e6e6e"], ["#cc79a7","#e3a6c8"]
+        ]),
+        grace: Palette::from_strs([
+          ["#2e2e40","#38384c"], ["#005a8c","#0072b2"], ["#3a8fbf","#56b4e9"],
+          ["#b37a00","#e69f00"], ["#f0e442","#f7ef8a"], ["#a35a85","#cc79a7"]
+        ]),
+        background: ["#1a1a2e".into(), "#e8e8f0".into()]
+      },
+      Theme {
+        name: "Protanopia".into(),
+        normal: Palette::from_strs([
+          ["#1a1a2e","#23233a"], ["#0072b2","#3a9ad9"], ["#56b4e9","#9fd6f5"],
+          ["#f0e442","#faf3a0"], ["#5a5a5a","#6e6e6e"], ["#cc79a7","#e3a6c8"]
+        ]),
+        grace: Palette::from_strs([
+          ["#2e2e40","#38384c"], ["#005a8c","#0072b2"], ["#3a8fbf","#56b4e9"],
+          ["#c4b934","#f0e442"], ["#e69f00","#ffc14d"], ["#a35a85","#cc79a7"]
+        ]),
+        background: ["#1a1a2e".into(), "#e8e8f0".into()]
+      },
+      Theme {
+        name: "Tritanopia".into(),
+        normal: Palette::from_strs([
+          ["#1c1c1c","#262626"], ["#009e9e","#33c4c4"], ["#b2ebeb","#ffffff"],
+          ["#d81b60","#f06292"], ["#5a5a5a","#6e6e6e"], ["#f4a6a6","#fcd4d4"]
+        ]),
+        grace: Palette::from_strs([
+          ["#2e2424","#3a2e2e"], ["#007777","#009e9e"], ["#80cccc","#b2ebeb"],
+          ["#a3144a","#d81b60"], ["#ff8a80","#ffb8b0"], ["#c47a7a","#f4a6a6"]
+        ]),
+        background: ["#1c1c1c".into(), "#f0e8e8".into()]
+      },
+    ]
+  }
+}